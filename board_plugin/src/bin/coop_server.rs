@@ -0,0 +1,85 @@
+use bevy::app::ScheduleRunnerSettings;
+use bevy::log::{self, LogPlugin};
+use bevy::prelude::*;
+use board_plugin::net::{CoopServer, DEFAULT_COOP_ADDRESS};
+use board_plugin::BoardOptions;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Usage: coop_server [--address ADDRESS] [--size WxH] [--bombs N] [--seed N] [--no-safe-start]";
+
+fn main() {
+    let (address, options) = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let server = match CoopServer::bind(&address, options) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {}", address, e);
+            std::process::exit(1);
+        }
+    };
+    App::new()
+        .insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_millis(5)))
+        .add_plugins(MinimalPlugins)
+        .add_plugin(LogPlugin)
+        .insert_resource(server)
+        .add_startup_system(log_address)
+        .add_system(step_server)
+        .run();
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(String, BoardOptions), String> {
+    let mut address = DEFAULT_COOP_ADDRESS.to_string();
+    let mut options = BoardOptions {
+        map_size: (60, 40),
+        bomb_count: 400,
+        safe_start: true,
+        ..Default::default()
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--address" => address = value()?,
+            "--bombs" => options.bomb_count = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--seed" => options.seed = Some(value()?.parse().map_err(|e| format!("{}", e))?),
+            "--size" => {
+                let size = value()?;
+                let (width, height) = size
+                    .split_once('x')
+                    .ok_or(format!("Invalid size {}", size))?;
+                options.map_size = (
+                    width.parse().map_err(|e| format!("{}", e))?,
+                    height.parse().map_err(|e| format!("{}", e))?,
+                );
+            }
+            "--no-safe-start" => options.safe_start = false,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    let (width, height) = options.map_size;
+    if width == 0
+        || height == 0
+        || u32::from(options.bomb_count) >= u32::from(width) * u32::from(height)
+    {
+        return Err("The board needs at least one safe tile".to_string());
+    }
+    Ok((address, options))
+}
+
+fn log_address(server: Res<CoopServer>) {
+    match server.local_addr() {
+        Ok(address) => log::info!("Co-op server listening on {}", address),
+        Err(e) => log::error!("{}", e),
+    }
+}
+
+fn step_server(mut server: ResMut<CoopServer>) {
+    if let Err(e) = server.step() {
+        log::error!("Co-op server error: {}", e);
+    }
+}
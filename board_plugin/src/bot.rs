@@ -0,0 +1,95 @@
+use crate::components::{FlagsGame, ReadOnlyBoard, RemoteBoard};
+use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::Board;
+use bevy::ecs::schedule::StateData;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use minesweeper_core::{BotAction, BotMove};
+use std::time::Duration;
+
+/// Bot player plugin.
+///
+/// Plays every local classic board while `running_state` is active, sending one
+/// `TileTriggerEvent` or `TileMarkEvent` per board every `interval`. Deductions come first, then
+/// the lowest bomb probability guess, and every move is logged with its reason
+pub struct BotPlugin<T> {
+    pub running_state: T,
+    /// Delay between two moves
+    pub interval: Duration,
+    /// Does the bot start playing right away
+    pub enabled: bool,
+}
+
+/// Bot player state. Must be used as a resource.
+#[derive(Debug)]
+pub struct Bot {
+    /// The bot only plays while enabled
+    pub enabled: bool,
+    /// Move timer, its duration is the delay between two moves
+    pub timer: Timer,
+    /// Last move sent on every board, not applied yet when found again
+    last_moves: HashMap<Entity, BotMove>,
+}
+
+impl Bot {
+    #[must_use]
+    pub fn new(interval: Duration, enabled: bool) -> Self {
+        Self {
+            enabled,
+            timer: Timer::new(interval, true),
+            last_moves: HashMap::default(),
+        }
+    }
+}
+
+impl<T: StateData> Plugin for BotPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Bot::new(self.interval, self.enabled))
+            .add_system_set(SystemSet::on_update(self.running_state.clone()).with_system(bot_play));
+        log::info!("Loaded Bot Plugin");
+    }
+}
+
+/// Sends the next bot move of every playable board
+#[allow(clippy::type_complexity)]
+pub fn bot_play(
+    time: Res<Time>,
+    mut bot: ResMut<Bot>,
+    boards: Query<
+        (Entity, &Board),
+        (
+            Without<FlagsGame>,
+            Without<RemoteBoard>,
+            Without<ReadOnlyBoard>,
+        ),
+    >,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+) {
+    if !bot.enabled || !bot.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for (entity, board) in boards.iter() {
+        let bot_move = match BotMove::find(&board.game) {
+            Some(m) => m,
+            None => continue,
+        };
+        // The previous move is found again until the board handled it
+        if bot.last_moves.remove(&entity) == Some(bot_move) {
+            continue;
+        }
+        log::info!("Bot: {}", bot_move);
+        match bot_move.action {
+            BotAction::Reveal => tile_trigger_ewr.send(TileTriggerEvent {
+                board: entity,
+                coords: bot_move.coords,
+            }),
+            BotAction::Flag => tile_mark_ewr.send(TileMarkEvent {
+                board: entity,
+                coords: bot_move.coords,
+            }),
+        }
+        bot.last_moves.insert(entity, bot_move);
+    }
+}
@@ -1,4 +1,4 @@
-use bevy::prelude::Vec2;
+use bevy::prelude::{GlobalTransform, Node, Vec2};
 
 #[derive(Debug, Copy, Clone)]
 pub struct Bounds2 {
@@ -15,4 +15,24 @@ impl Bounds2 {
             && coords.x <= self.position.x + self.size.x
             && coords.y <= self.position.y + self.size.y
     }
+
+    /// Window area of a UI node, from its computed size and global transform
+    #[inline]
+    #[must_use]
+    pub fn ui_node(node: &Node, transform: &GlobalTransform) -> Self {
+        Self {
+            position: transform.translation.truncate() - node.size / 2.,
+            size: node.size,
+        }
+    }
+}
+
+/// Is the window `cursor` position over any of the UI `nodes`, interactive or not
+pub fn cursor_over_ui<'a>(
+    cursor: Vec2,
+    nodes: impl IntoIterator<Item = (&'a Node, &'a GlobalTransform)>,
+) -> bool {
+    nodes
+        .into_iter()
+        .any(|(node, transform)| Bounds2::ui_node(node, transform).in_bounds(cursor))
 }
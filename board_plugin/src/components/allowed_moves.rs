@@ -0,0 +1,28 @@
+use crate::components::Coordinates;
+use crate::PressAction;
+use bevy::prelude::Component;
+use bevy::utils::HashSet;
+
+/// Restricts the player input on a board, lives on the board entity next to the `Board`.
+///
+/// Clicks on other tiles are ignored, scripted boards like tutorials use it to guide the player
+#[derive(Debug, Clone, Default, Component)]
+pub struct AllowedMoves {
+    /// Tiles which can be uncovered
+    pub uncover: HashSet<Coordinates>,
+    /// Tiles which can be flagged or unflagged
+    pub mark: HashSet<Coordinates>,
+    /// Uncovered tiles which can be chorded
+    pub chord: HashSet<Coordinates>,
+}
+
+impl AllowedMoves {
+    /// Can a press `action` be performed on `coords`
+    #[must_use]
+    pub fn allows(&self, action: PressAction, coords: &Coordinates) -> bool {
+        match action {
+            PressAction::Uncover => self.uncover.contains(coords),
+            PressAction::Chord => self.chord.contains(coords),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::components::Coordinates;
+use crate::{BoardOptions, Bounds2};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::{HashMap, HashSet};
+use minesweeper_core::{BoardCode, Game};
+
+/// Board component, displays a `Game` and lives on the board entity
+#[derive(Debug, Component)]
+pub struct Board {
+    /// Game rules and state
+    pub game: Game,
+    pub bounds: Bounds2,
+    pub tile_size: f32,
+    /// Cover entities of the tiles still displayed as covered
+    pub covered_tiles: HashMap<Coordinates, Entity>,
+    /// Covered tiles marked with a question mark, which has no effect on the game
+    pub question_marks: HashSet<Coordinates>,
+    /// Options the board was generated with
+    pub options: BoardOptions,
+}
+
+impl Board {
+    /// Translates a mouse position to board coordinates
+    pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinates> {
+        // Window to world space
+        let window_size = Vec2::new(window.width(), window.height());
+        let position = position - window_size / 2.;
+
+        // Bounds check
+        if !self.bounds.in_bounds(position) {
+            return None;
+        }
+        // World space to board space
+        let coordinates = position - self.bounds.position;
+        Some(Coordinates {
+            x: (coordinates.x / self.tile_size) as u16,
+            y: (coordinates.y / self.tile_size) as u16,
+        })
+    }
+
+    /// Retrieves the cover entity of a tile a trigger would uncover
+    pub fn tile_to_uncover(&self, coords: &Coordinates) -> Option<&Entity> {
+        if self.game.is_flagged(*coords) {
+            None
+        } else {
+            self.covered_tiles.get(coords)
+        }
+    }
+
+    /// Retrieves the cover entities of the given tiles
+    pub fn cover_entities(&self, tiles: &[Coordinates]) -> Vec<Entity> {
+        tiles
+            .iter()
+            .filter_map(|c| self.covered_tiles.get(c))
+            .copied()
+            .collect()
+    }
+
+    /// Flags or unflags a tile, returning the cover entity and if the tile is flagged
+    pub fn try_toggle_mark(&mut self, coords: &Coordinates) -> Option<(Entity, bool)> {
+        let entity = *self.covered_tiles.get(coords)?;
+        match self.game.toggle_flag(*coords) {
+            Ok(mark) => Some((entity, mark)),
+            Err(e) => {
+                log::debug!("Failed to mark tile: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Retrieves the cover entities of the unflagged neighbors of `coord`
+    pub fn adjacent_unmarked_tiles(&self, coord: Coordinates) -> Vec<Entity> {
+        self.game
+            .tile_map()
+            .safe_square_at(coord)
+            .filter(|c| !self.game.is_flagged(*c))
+            .filter_map(|c| self.covered_tiles.get(&c))
+            .copied()
+            .collect()
+    }
+
+    /// Code of the board, to play it elsewhere.
+    ///
+    /// Generated boards are shared by their seed, hand-made boards by their bomb layout, both with
+    /// their start cell
+    #[must_use]
+    pub fn code(&self) -> BoardCode {
+        let mut code = BoardCode::seeded(&self.options.game_options()).unwrap_or_else(|| {
+            BoardCode::from_tile_map(self.game.tile_map(), self.options.safe_start)
+        });
+        code.start = self.options.start;
+        code
+    }
+
+    /// Is every tile uncovered by the game displayed as uncovered
+    #[inline]
+    #[must_use]
+    pub fn is_displayed(&self) -> bool {
+        self.covered_tiles.len() == self.game.covered_count()
+    }
+}
@@ -0,0 +1,6 @@
+use bevy::prelude::Component;
+
+/// Pip pattern of a bomb counter, scaled to the tile size
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Debug, Copy, Clone, Component)]
+pub struct CounterMarker;
@@ -0,0 +1,68 @@
+use bevy::prelude::Component;
+
+/// Outcome of a finished flags game
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FlagsOutcome {
+    /// The player claimed the most bombs
+    Winner(u8),
+    /// Several players share the best score
+    Draw,
+}
+
+/// Hot-seat competitive "Flags" game state, lives on the board entity next to the `Board`.
+///
+/// Players take turns uncovering tiles, uncovering a bomb claims it and gives another turn
+#[derive(Debug, Clone, Component)]
+pub struct FlagsGame {
+    /// Player whose turn it is
+    pub current_player: u8,
+    /// Claimed bombs per player
+    pub scores: Vec<u16>,
+    /// Outcome, once the game is over
+    pub outcome: Option<FlagsOutcome>,
+}
+
+impl FlagsGame {
+    /// Starts a game for `players` players, the first player begins
+    #[must_use]
+    pub fn new(players: u8) -> Self {
+        Self {
+            current_player: 0,
+            scores: vec![0; players.max(1) as usize],
+            outcome: None,
+        }
+    }
+
+    /// Is the game over
+    #[inline]
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Passes the turn to the next player
+    pub fn next_turn(&mut self) {
+        self.current_player = (self.current_player + 1) % self.scores.len() as u8;
+    }
+
+    /// Claims a bomb for `player`, the turn doesn't change.
+    ///
+    /// The game ends when a player holds a majority of the `bomb_count` bombs, or when every bomb
+    /// is claimed
+    pub fn claim_bomb(&mut self, player: u8, bomb_count: u16) {
+        self.scores[player as usize] += 1;
+        let best = *self.scores.iter().max().unwrap_or(&0);
+        let claimed: u16 = self.scores.iter().sum();
+        if best > bomb_count / 2 {
+            self.outcome = Some(FlagsOutcome::Winner(player));
+        } else if claimed >= bomb_count {
+            let leaders: Vec<usize> = (0..self.scores.len())
+                .filter(|p| self.scores[*p] == best)
+                .collect();
+            self.outcome = Some(match leaders.as_slice() {
+                [player] => FlagsOutcome::Winner(*player as u8),
+                _ => FlagsOutcome::Draw,
+            });
+        }
+    }
+}
@@ -1,6 +1,15 @@
-pub use {bomb::*, bomb_neighbor::*, coordinates::*, uncover::*};
+pub use minesweeper_core::Coordinates;
+pub use {
+    allowed_moves::*, board::*, bomb::*, bomb_neighbor::*, counter_marker::*, flags_game::*,
+    read_only_board::*, remote_board::*, uncover::*,
+};
 
+mod allowed_moves;
+mod board;
 mod bomb;
 mod bomb_neighbor;
-mod coordinates;
+mod counter_marker;
+mod flags_game;
+mod read_only_board;
+mod remote_board;
 mod uncover;
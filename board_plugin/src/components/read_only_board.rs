@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// Read only board component, lives on boards which only mirror a game played elsewhere.
+///
+/// Player input is ignored on these boards
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Debug, Copy, Clone, Component)]
+pub struct ReadOnlyBoard;
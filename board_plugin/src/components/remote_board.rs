@@ -0,0 +1,8 @@
+use bevy::prelude::Component;
+
+/// Remote board component, lives on boards whose moves are decided by a server.
+///
+/// Input events are still sent for the network layer to forward, but they are not applied locally
+#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
+#[derive(Debug, Copy, Clone, Component)]
+pub struct RemoteBoard;
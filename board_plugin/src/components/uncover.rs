@@ -1,6 +1,9 @@
 use bevy::prelude::Component;
 
-/// Uncover component, indicates a covered tile that should be uncovered
+/// Uncover component, indicates a tile cover to remove once the game uncovered its tile
 #[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Copy, Clone, Component)]
-pub struct Uncover;
+#[derive(Debug, Copy, Clone, Default, Component)]
+pub struct Uncover {
+    /// Player claiming the uncovered bomb in flags games, recorded when the move is played
+    pub claimed_by: Option<u8>,
+}
@@ -1,12 +1,59 @@
 use crate::components::Coordinates;
+use bevy::prelude::Entity;
+use minesweeper_core::Tile;
 
+// Every event carries the entity of the `Board` it applies to
+
+#[derive(Debug, Copy, Clone)]
+pub struct TileMarkEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TileTriggerEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+}
+
+/// Uncovers the covered neighbors of an uncovered tile if all its bombs are marked
 #[derive(Debug, Copy, Clone)]
-pub struct TileMarkEvent(pub Coordinates);
+pub struct TileChordEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+}
 
 #[derive(Debug, Copy, Clone)]
-pub struct TileTriggerEvent(pub Coordinates);
+pub struct BoardCompletedEvent {
+    pub board: Entity,
+}
 
+/// A bomb was uncovered on `coords`
 #[derive(Debug, Copy, Clone)]
-pub struct BoardCompletedEvent;
+pub struct BombExplosionEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+}
+
+/// A tile cover was removed, sent for every tile of a cascade
+#[derive(Debug, Copy, Clone)]
+pub struct TileUncoveredEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+    pub tile: Tile,
+}
+
+/// A tile was flagged or unflagged by a `TileMarkEvent`
+#[derive(Debug, Copy, Clone)]
+pub struct TileFlaggedEvent {
+    pub board: Entity,
+    pub coords: Coordinates,
+    pub flagged: bool,
+}
+
+/// The tiles uncovered by a move are all displayed, sent the same frame as their `TileUncoveredEvent`s
 #[derive(Debug, Copy, Clone)]
-pub struct BombExplosionEvent;
+pub struct CascadeFinishedEvent {
+    pub board: Entity,
+    pub tiles_revealed: usize,
+}
@@ -1,92 +1,299 @@
-use crate::components::{Bomb, BombNeighbor, Coordinates, Uncover};
+use crate::components::{Bomb, BombNeighbor, Coordinates, FlagsGame};
 use crate::events::*;
-use crate::resources::tile::Tile;
-use crate::tile_map::TileMap;
-use bevy::ecs::schedule::StateData;
+use bevy::ecs::schedule::{RunCriteria, ShouldRun, StateData};
+use bevy::ecs::system::BoxedSystem;
 use bevy::log;
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
 use bevy::utils::{AHashExt, HashMap};
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::RegisterInspectable;
+pub use bot::*;
 pub use bounds::*;
+pub use components::Board;
+use minesweeper_core::{Game, Tile};
 pub use resources::*;
 
+mod bot;
 mod bounds;
 pub mod components;
 pub mod events;
+pub use minesweeper_core;
+#[cfg(feature = "net")]
+pub mod net;
 mod resources;
 mod systems;
 
+/// Board plugin, built with `BoardPlugin::new` or `BoardPlugin::with_run_criteria`
 pub struct BoardPlugin<T> {
-    pub running_state: T,
+    lifecycle: BoardLifecycle<T>,
+    headless: bool,
+    input: bool,
+}
+
+/// Decides when the board systems run
+pub enum BoardLifecycle<T> {
+    /// Boards are created when entering the state and cleaned up when exiting it.
+    /// They are played while the state is active, and laid out while it is in the stack
+    State(T),
+    /// Boards are played while the run criteria returns `ShouldRun::Yes`, and created when none exists.
+    /// The app cleans them up with the `BoardPlugin::cleanup_board` system
+    RunCriteria(Box<dyn Fn() -> BoxedSystem<(), ShouldRun> + Send + Sync>),
+}
+
+/// Labels of the board systems, to order app systems around them
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemLabel)]
+pub enum BoardSystem {
+    /// Mouse input, turned into board events
+    Input,
+    /// Trigger and chord events handling
+    Trigger,
+    /// Tile covers removal
+    Uncover,
+    /// Mark events handling
+    Mark,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, RunCriteriaLabel)]
+struct BoardRunCriteria;
+
+impl<T> BoardPlugin<T> {
+    /// Plugin running the boards in `running_state`
+    #[must_use]
+    pub fn new(running_state: T) -> Self {
+        Self {
+            lifecycle: BoardLifecycle::State(running_state),
+            headless: false,
+            input: true,
+        }
+    }
+
+    /// Runs the game without window, input or rendering, for servers and tests.
+    ///
+    /// Boards are then only played through the board events
+    #[must_use]
+    pub fn headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Turns the built-in mouse input on or off, the app can then send its own board events
+    #[must_use]
+    pub fn input(mut self, input: bool) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Systems creating the boards
+    fn enter_set(&self) -> SystemSet
+    where
+        T: StateData,
+    {
+        match &self.lifecycle {
+            BoardLifecycle::State(state) => SystemSet::on_enter(state.clone()),
+            BoardLifecycle::RunCriteria(_) => SystemSet::new()
+                .with_run_criteria(RunCriteria::pipe(BoardRunCriteria, missing_board.system())),
+        }
+    }
+
+    /// Systems running while the boards are active
+    fn update_set(&self) -> SystemSet
+    where
+        T: StateData,
+    {
+        match &self.lifecycle {
+            BoardLifecycle::State(state) => SystemSet::on_update(state.clone()),
+            BoardLifecycle::RunCriteria(_) => SystemSet::new().with_run_criteria(BoardRunCriteria),
+        }
+    }
+
+    /// Systems running while the boards exist, even if inactive
+    fn in_stack_set(&self) -> SystemSet
+    where
+        T: StateData,
+    {
+        match &self.lifecycle {
+            BoardLifecycle::State(state) => SystemSet::on_in_stack_update(state.clone()),
+            BoardLifecycle::RunCriteria(_) => SystemSet::new().with_run_criteria(BoardRunCriteria),
+        }
+    }
+}
+
+impl BoardPlugin<()> {
+    /// Plugin running the boards while `run_criteria` returns `ShouldRun::Yes`, without any state
+    #[must_use]
+    pub fn with_run_criteria<S, P>(run_criteria: S) -> Self
+    where
+        S: IntoSystem<(), ShouldRun, P> + Clone + Send + Sync + 'static,
+    {
+        Self {
+            lifecycle: BoardLifecycle::RunCriteria(Box::new(move || {
+                Box::new(run_criteria.clone().system())
+            })),
+            headless: false,
+            input: true,
+        }
+    }
+}
+
+/// Creates the boards only when none exists
+fn missing_board(In(should_run): In<ShouldRun>, boards: Query<(), With<Board>>) -> ShouldRun {
+    match should_run {
+        ShouldRun::Yes if boards.is_empty() => ShouldRun::Yes,
+        _ => ShouldRun::No,
+    }
 }
 
 impl<T: StateData> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
-        // When the running states comes into the stack we load a board
+        if let BoardLifecycle::RunCriteria(criteria) = &self.lifecycle {
+            // The app run criteria is evaluated once per frame, every board system set refers to its label
+            app.add_system_set(
+                SystemSet::new().with_run_criteria(criteria().label(BoardRunCriteria)),
+            );
+        }
+        if self.headless {
+            // When the boards start we load them
+            app.add_system_set(self.enter_set().with_system(Self::create_headless_board));
+        } else {
+            app.add_system_set(self.enter_set().with_system(Self::create_board))
+                .add_system_set(
+                    self.in_stack_set()
+                        .with_system(systems::layout::relayout_board)
+                        .with_system(systems::theme::apply_board_assets)
+                        .with_system(systems::theme::apply_board_materials),
+                );
+            if self.input {
+                // We handle input only if the boards are active
+                app.add_system_set(
+                    self.update_set()
+                        .label(BoardSystem::Input)
+                        .with_system(systems::input::input_handling)
+                        .with_system(systems::input::press_preview),
+                );
+            }
+        }
+        // We handle trigger events only if the boards are active
         app.add_system_set(
-            SystemSet::on_enter(self.running_state.clone()).with_system(Self::create_board),
-        )
-        // We handle input and trigger events only if the state is active
-        .add_system_set(
-            SystemSet::on_update(self.running_state.clone())
-                .with_system(systems::input::input_handling)
-                .with_system(systems::uncover::trigger_event_handler),
+            self.update_set()
+                .with_system(systems::uncover::trigger_event_handler.label(BoardSystem::Trigger))
+                .with_system(systems::uncover::chord_event_handler.label(BoardSystem::Trigger)),
         )
-        // We handle uncovering even if the state is inactive
+        // Uncovering only happens while the boards are active, a paused cascade resumes with the game
         .add_system_set(
-            SystemSet::on_in_stack_update(self.running_state.clone())
-                .with_system(systems::uncover::uncover_tiles)
-                .with_system(systems::mark::mark_tiles),
-        )
-        .add_system_set(
-            SystemSet::on_exit(self.running_state.clone()).with_system(Self::cleanup_board),
-        )
-        .add_event::<TileTriggerEvent>()
-        .add_event::<TileMarkEvent>()
-        .add_event::<BombExplosionEvent>()
-        .add_event::<BoardCompletedEvent>();
+            self.update_set()
+                .with_system(systems::uncover::uncover_tiles.label(BoardSystem::Uncover))
+                .with_system(systems::mark::mark_tiles.label(BoardSystem::Mark)),
+        );
+        if let BoardLifecycle::State(state) = &self.lifecycle {
+            app.add_system_set(SystemSet::on_exit(state.clone()).with_system(Self::cleanup_board));
+        }
+        app.add_event::<TileTriggerEvent>()
+            .add_event::<TileMarkEvent>()
+            .add_event::<TileChordEvent>()
+            .add_event::<BombExplosionEvent>()
+            .add_event::<BoardCompletedEvent>()
+            .add_event::<TileUncoveredEvent>()
+            .add_event::<TileFlaggedEvent>()
+            .add_event::<CascadeFinishedEvent>()
+            .init_resource::<TilePress>();
         #[cfg(feature = "debug")]
         {
             // registering custom component to be able to edit it in inspector
             app.register_inspectable::<Bomb>()
                 .register_inspectable::<Coordinates>()
                 .register_inspectable::<BombNeighbor>()
-                .register_inspectable::<Uncover>();
+                .register_inspectable::<components::CounterMarker>()
+                .register_inspectable::<components::Uncover>()
+                .register_inspectable::<components::RemoteBoard>()
+                .register_inspectable::<components::ReadOnlyBoard>();
         }
         log::info!("Loaded Board Plugin");
     }
 }
 
 impl<T> BoardPlugin<T> {
-    /// System to generate the complete board
+    /// System to generate the complete boards
     pub fn create_board(
         mut commands: Commands,
         board_options: Option<Res<BoardOptions>>,
+        board_options_set: Option<Res<BoardOptionsSet>>,
         windows: Res<Windows>,
         board_assets: Res<BoardAssets>,
     ) {
-        let options = board_options.map_or(
-            BoardOptions::default(), /* If no options is set we use the default one */
-            |o| o.clone(),
-        );
-        // TileMap generation
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
+        let window = windows.get_primary().unwrap();
+        for options in Self::options_to_spawn(board_options, board_options_set) {
+            Self::spawn_board(&mut commands, options, Some(window), Some(&board_assets));
+        }
+    }
+
+    /// System to generate the complete boards without any window or rendering
+    pub fn create_headless_board(
+        mut commands: Commands,
+        board_options: Option<Res<BoardOptions>>,
+        board_options_set: Option<Res<BoardOptionsSet>>,
+    ) {
+        for options in Self::options_to_spawn(board_options, board_options_set) {
+            Self::spawn_board(&mut commands, options, None, None);
+        }
+    }
+
+    /// Retrieves the options of every board to spawn
+    fn options_to_spawn(
+        board_options: Option<Res<BoardOptions>>,
+        board_options_set: Option<Res<BoardOptionsSet>>,
+    ) -> Vec<BoardOptions> {
+        match board_options_set {
+            Some(set) => set.0.clone(),
+            None => vec![board_options.map_or(
+                BoardOptions::default(), /* If no options is set we use the default one */
+                |o| o.clone(),
+            )],
+        }
+    }
+
+    /// Generates a complete board entity with its `Board` component.
+    ///
+    /// Without `window` the board is laid out with its largest tile size, and without
+    /// `board_assets` no sprite or text is spawned
+    fn spawn_board(
+        commands: &mut Commands,
+        mut options: BoardOptions,
+        window: Option<&Window>,
+        board_assets: Option<&BoardAssets>,
+    ) -> Entity {
+        // Game generation
+        let mut game = match &options.tile_map {
+            Some(tile_map) => {
+                options.map_size = (tile_map.width(), tile_map.height());
+                options.bomb_count = tile_map.bomb_count();
+                options.seed = None;
+                Game::from_tile_map(tile_map.clone(), options.safe_start)
+            }
+            None => {
+                // The seed is kept on the board so it can be generated again
+                options.seed.get_or_insert_with(rand::random);
+                Game::new(&options.game_options())
+            }
+        };
+        if let Some(start) = options.start {
+            if let Err(e) = game.reveal(start) {
+                log::error!("Failed to uncover the start cell {}: {:?}", start, e);
+            }
+        }
+        let tile_map = game.tile_map();
         #[cfg(feature = "debug")]
         // TileMap debugging
         log::info!("{}", tile_map.console_output());
 
         // Setup
         // We define the size of our tiles in world space
-        let tile_size = match options.tile_size {
-            TileSize::Fixed(v) => v,
-            TileSize::Adaptive { min, max } => Self::adaptive_tile_size(
-                windows.get_primary().unwrap(),
-                (min, max),
-                (tile_map.width(), tile_map.height()),
-            ),
+        let tile_size = match (window, &options.tile_size) {
+            (Some(window), _) => {
+                options.tile_size_for(window, (tile_map.width(), tile_map.height()))
+            }
+            (None, TileSize::Fixed(v)) => *v,
+            (None, TileSize::Adaptive { max, .. }) => *max,
         };
         // We deduce the size of the complete board
         let board_size = Vec2::new(
@@ -95,16 +302,14 @@ impl<T> BoardPlugin<T> {
         );
         log::info!("board size: {}", board_size);
         // We define the board anchor position (bottom left)
-        let board_position = match options.position {
-            BoardPosition::Centered { offset } => {
-                Vec3::new(-(board_size.x / 2.), -(board_size.y / 2.), 0.) + offset
-            }
-            BoardPosition::Custom(p) => p,
+        let board_position = match (window, &options.position) {
+            (Some(window), _) => options.board_position(window, board_size),
+            (None, BoardPosition::Custom(p)) => *p,
+            (None, _) => Vec3::ZERO,
         };
 
         let mut covered_tiles =
-            HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
-        let mut safe_start = None;
+            HashMap::with_capacity(usize::from(tile_map.width()) * usize::from(tile_map.height()));
         let board_entity = commands
             .spawn()
             .insert(Name::new("Board"))
@@ -113,60 +318,66 @@ impl<T> BoardPlugin<T> {
             .insert(GlobalTransform::default())
             .with_children(|parent| {
                 // We spawn the board background sprite at the center of the board, since the sprite pivot is centered
-                parent
-                    .spawn_bundle(SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(board_size),
-                            color: board_assets.board_material.color,
+                if let Some(board_assets) = board_assets {
+                    parent
+                        .spawn_bundle(SpriteBundle {
+                            sprite: Sprite {
+                                custom_size: Some(board_size),
+                                color: board_assets.board_material.color,
+                                ..Default::default()
+                            },
+                            texture: board_assets.board_material.texture.clone(),
+                            transform: Transform::from_xyz(
+                                board_size.x / 2.,
+                                board_size.y / 2.,
+                                0.,
+                            ),
                             ..Default::default()
-                        },
-                        texture: board_assets.board_material.texture.clone(),
-                        transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
-                        ..Default::default()
-                    })
-                    .insert(Name::new("Background"));
+                        })
+                        .insert(Name::new("Background"));
+                }
                 // Tiles
                 Self::spawn_tiles(
                     parent,
-                    &tile_map,
+                    &game,
                     tile_size,
                     options.tile_padding,
                     board_assets,
                     &mut covered_tiles,
-                    &mut safe_start,
                 );
             })
             .id();
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
-            }
+        if let GameMode::Flags { players } = options.game_mode {
+            commands
+                .entity(board_entity)
+                .insert(FlagsGame::new(players));
         }
-        // We add the main resource of the game, the board
-        commands.insert_resource(Board {
-            tile_map,
+        // We add the main component of the game, the board
+        commands.entity(board_entity).insert(Board {
+            game,
             bounds: Bounds2 {
                 position: board_position.xy(),
                 size: board_size,
             },
             tile_size,
             covered_tiles,
-            marked_tiles: Vec::new(),
-            entity: board_entity,
+            question_marks: Default::default(),
+            options,
         });
+        board_entity
     }
 
+    /// Spawns the tiles of `game`, with a cover on every covered tile
     fn spawn_tiles(
         parent: &mut ChildBuilder,
-        tile_map: &TileMap,
+        game: &Game,
         size: f32,
         padding: f32,
-        board_assets: Res<BoardAssets>,
+        board_assets: Option<&BoardAssets>,
         covered_tiles: &mut HashMap<Coordinates, Entity>,
-        safe_start_entity: &mut Option<Entity>,
     ) {
         // Tiles
-        for (y, line) in tile_map.iter().enumerate() {
+        for (y, line) in game.tile_map().iter().enumerate() {
             for (x, tile) in line.iter().enumerate() {
                 let coordinates = Coordinates {
                     x: x as u16,
@@ -175,73 +386,87 @@ impl<T> BoardPlugin<T> {
                 let mut cmd = parent.spawn();
 
                 // Tile sprite
-                cmd.insert_bundle(SpriteBundle {
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::splat(size - padding)),
-                        color: board_assets.tile_material.color,
+                if let Some(board_assets) = board_assets {
+                    cmd.insert_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(size - padding)),
+                            color: board_assets.tile_material.color,
+                            ..Default::default()
+                        },
+                        texture: board_assets.tile_material.texture.clone(),
+                        transform: Transform::from_xyz(
+                            (x as f32 * size) + (size / 2.),
+                            (y as f32 * size) + (size / 2.),
+                            1.,
+                        ),
                         ..Default::default()
-                    },
-                    texture: board_assets.tile_material.texture.clone(),
-                    transform: Transform::from_xyz(
-                        (x as f32 * size) + (size / 2.),
-                        (y as f32 * size) + (size / 2.),
-                        1.,
-                    ),
-                    ..Default::default()
-                })
+                    });
+                }
                 // Tile name
-                .insert(Name::new(format!("Tile ({}, {})", x, y)))
-                // Tile coordinates
-                .insert(coordinates)
-                // Children
-                .with_children(|parent| {
-                    let mut child_cmd = parent.spawn();
-                    // Tile cover
-                    let entity = child_cmd
-                        .insert_bundle(SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assets.covered_tile_material.color,
+                cmd.insert(Name::new(format!("Tile ({}, {})", x, y)))
+                    // Tile coordinates
+                    .insert(coordinates)
+                    // Children
+                    .with_children(|parent| {
+                        if !game.is_covered(coordinates) {
+                            return;
+                        }
+                        let mut child_cmd = parent.spawn();
+                        // Tile cover
+                        if let Some(board_assets) = board_assets {
+                            child_cmd.insert_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(size - padding)),
+                                    color: board_assets.covered_tile_material.color,
+                                    ..Default::default()
+                                },
+                                transform: Transform::from_xyz(0., 0., 2.),
+                                texture: board_assets.covered_tile_material.texture.clone(),
                                 ..Default::default()
-                            },
-                            transform: Transform::from_xyz(0., 0., 2.),
-                            texture: board_assets.covered_tile_material.texture.clone(),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Tile Cover"))
-                        .id();
-                    covered_tiles.insert(coordinates, entity);
-                    if safe_start_entity.is_none() && *tile == Tile::Empty {
-                        *safe_start_entity = Some(entity);
-                    }
-                });
+                            });
+                        }
+                        let entity = child_cmd.insert(Name::new("Tile Cover")).id();
+                        covered_tiles.insert(coordinates, entity);
+                    });
                 match tile {
                     // If the tile is a bomb we add the matching component and a sprite child
                     Tile::Bomb => {
                         cmd.insert(Bomb);
-                        cmd.with_children(|child_cmd| {
-                            child_cmd.spawn_bundle(SpriteBundle {
-                                sprite: Sprite {
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    color: board_assets.bomb_material.color,
+                        if let Some(board_assets) = board_assets {
+                            cmd.with_children(|child_cmd| {
+                                child_cmd.spawn_bundle(SpriteBundle {
+                                    sprite: Sprite {
+                                        custom_size: Some(Vec2::splat(size - padding)),
+                                        color: board_assets.bomb_material.color,
+                                        ..Default::default()
+                                    },
+                                    transform: Transform::from_xyz(0., 0., 1.),
+                                    texture: board_assets.bomb_material.texture.clone(),
                                     ..Default::default()
-                                },
-                                transform: Transform::from_xyz(0., 0., 1.),
-                                texture: board_assets.bomb_material.texture.clone(),
-                                ..Default::default()
+                                });
                             });
-                        });
+                        }
                     }
                     // If the tile is a bomb neighbour we add the matching component and a text child
                     Tile::BombNeighbor(v) => {
                         cmd.insert(BombNeighbor { count: *v });
-                        cmd.with_children(|child_cmd| {
-                            child_cmd.spawn_bundle(Self::bomb_count_text_bundle(
-                                *v,
-                                &board_assets,
-                                size - padding,
-                            ));
-                        });
+                        if let Some(board_assets) = board_assets {
+                            cmd.with_children(|child_cmd| {
+                                child_cmd.spawn_bundle(Self::bomb_count_text_bundle(
+                                    *v,
+                                    board_assets,
+                                    size - padding,
+                                ));
+                                if board_assets.counter_markers {
+                                    systems::theme::spawn_counter_marker(
+                                        child_cmd,
+                                        *v,
+                                        board_assets.bomb_counter_color(*v),
+                                        size - padding,
+                                    );
+                                }
+                            });
+                        }
                     }
                     Tile::Empty => (),
                 }
@@ -250,11 +475,7 @@ impl<T> BoardPlugin<T> {
     }
 
     /// Generates the bomb counter text 2D Bundle for a given value
-    fn bomb_count_text_bundle(
-        count: u8,
-        board_assets: &Res<BoardAssets>,
-        size: f32,
-    ) -> Text2dBundle {
+    fn bomb_count_text_bundle(count: u8, board_assets: &BoardAssets, size: f32) -> Text2dBundle {
         // We retrieve the text and the correct color
         let color = board_assets.bomb_counter_color(count);
         // We generate a text bundle
@@ -278,19 +499,11 @@ impl<T> BoardPlugin<T> {
         }
     }
 
-    /// Computes a tile size that matches the window according to the tile map size
-    fn adaptive_tile_size(
-        window: &Window,
-        (min, max): (f32, f32),
-        (width, height): (u16, u16),
-    ) -> f32 {
-        let max_width = window.width() / width as f32;
-        let max_heigth = window.height() / height as f32;
-        max_width.min(max_heigth).clamp(min, max)
-    }
-
-    fn cleanup_board(board: Res<Board>, mut commands: Commands) {
-        commands.entity(board.entity).despawn_recursive();
-        commands.remove_resource::<Board>();
+    /// System despawning every board
+    pub fn cleanup_board(boards: Query<Entity, With<Board>>, mut commands: Commands) {
+        for board in boards.iter() {
+            commands.entity(board).despawn_recursive();
+        }
+        commands.insert_resource(TilePress::default());
     }
 }
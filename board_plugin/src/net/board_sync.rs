@@ -0,0 +1,104 @@
+use crate::components::{Coordinates, Uncover};
+use crate::net::{PlayerId, SyncMessage};
+use crate::systems::mark::{remove_flag, spawn_flag};
+use crate::{Board, BoardAssets};
+use bevy::prelude::*;
+
+/// Mirrors a server board on a local board entity.
+///
+/// A snapshot resets the mirror: its state is applied to the next spawned board, then every
+/// following update is applied to that board
+#[derive(Debug, Default)]
+pub(crate) struct BoardSync {
+    board: Option<Entity>,
+    awaiting_board: bool,
+    pending: Vec<SyncMessage>,
+}
+
+impl BoardSync {
+    /// The mirroring board, once spawned
+    #[inline]
+    pub fn board(&self) -> Option<Entity> {
+        self.board
+    }
+
+    /// Waits for a new board to apply a snapshot state on
+    pub fn reset(&mut self, uncovered: Vec<Coordinates>, marks: Vec<(Coordinates, PlayerId)>) {
+        self.board = None;
+        self.awaiting_board = true;
+        self.pending = vec![SyncMessage::Uncovered(uncovered)];
+        self.pending.extend(
+            marks
+                .into_iter()
+                .map(|(coords, owner)| SyncMessage::Marked {
+                    coords,
+                    owner: Some(owner),
+                }),
+        );
+    }
+
+    /// Queues an update for the mirroring board
+    #[inline]
+    pub fn push(&mut self, update: SyncMessage) {
+        self.pending.push(update);
+    }
+
+    /// Binds a newly spawned board, inserting `marker` on it, and applies the queued updates.
+    ///
+    /// Flags are drawn in their owner color if `owner_colors` is set
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply(
+        &mut self,
+        commands: &mut Commands,
+        marker: impl Component,
+        boards: &mut Query<&mut Board>,
+        new_boards: &Query<Entity, Added<Board>>,
+        children: &Query<&Children>,
+        board_assets: &BoardAssets,
+        owner_colors: bool,
+    ) {
+        if self.awaiting_board {
+            if let Some(entity) = new_boards.iter().next() {
+                commands.entity(entity).insert(marker);
+                self.board = Some(entity);
+                self.awaiting_board = false;
+            }
+        }
+        let mut board = match self.board.and_then(|e| boards.get_mut(e).ok()) {
+            Some(b) => b,
+            None => return,
+        };
+        for update in std::mem::take(&mut self.pending) {
+            match update {
+                SyncMessage::Uncovered(tiles) => {
+                    // Tiles are uncovered exactly as on the server, without local propagation
+                    let revealed: Vec<Coordinates> = tiles
+                        .into_iter()
+                        .filter(|c| board.game.reveal_tile(*c).unwrap_or(false))
+                        .collect();
+                    for entity in board.cover_entities(&revealed) {
+                        commands.entity(entity).insert(Uncover::default());
+                    }
+                }
+                SyncMessage::Marked { coords, owner } => {
+                    let marked = board.game.is_flagged(coords);
+                    if marked == owner.is_some() {
+                        continue;
+                    }
+                    if let Some((entity, mark)) = board.try_toggle_mark(&coords) {
+                        if mark {
+                            let color = match owner {
+                                Some(o) if owner_colors => board_assets.player_color(o as usize),
+                                _ => board_assets.flag_material.color,
+                            };
+                            spawn_flag(commands, entity, board.tile_size, board_assets, color);
+                        } else {
+                            remove_flag(commands, entity, children);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
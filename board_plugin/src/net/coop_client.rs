@@ -0,0 +1,274 @@
+use crate::components::{Coordinates, RemoteBoard};
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+use crate::net::{
+    add_net_board_event, BoardSync, ClientMessage, Connection, NetBoardEvent, PlayerId,
+    SyncMessage, MAX_SYNC_MESSAGE_LENGTH,
+};
+use crate::{Board, BoardAssets};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Co-op client plugin.
+///
+/// Joins the co-op server at `address`, forwards every move on the shared board to it and applies
+/// the uncovers and marks it broadcasts. Other players' cursors are shown in their color.
+/// The app is responsible for spawning the board on `NetBoardEvent`
+pub struct CoopClientPlugin {
+    /// Co-op server address
+    pub address: String,
+    /// Player name
+    pub name: String,
+}
+
+/// Co-op session state. Must be used as a resource.
+#[derive(Debug, Default)]
+pub struct CoopSession {
+    /// Our player id, once the server welcomed us
+    pub player: Option<PlayerId>,
+    /// Tile hovered by every other player
+    pub cursors: HashMap<PlayerId, Coordinates>,
+    sync: BoardSync,
+    hovered: Option<Coordinates>,
+}
+
+impl CoopSession {
+    /// The shared board entity, once spawned
+    #[inline]
+    #[must_use]
+    pub fn board(&self) -> Option<Entity> {
+        self.sync.board()
+    }
+}
+
+/// Other player cursor sprite
+#[derive(Debug, Copy, Clone, Component)]
+pub struct CoopCursor(pub PlayerId);
+
+/// Connection to the co-op server
+struct CoopConnection(Connection);
+
+struct CoopClientConfig {
+    address: String,
+    name: String,
+}
+
+impl Plugin for CoopClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(CoopClientConfig {
+            address: self.address.clone(),
+            name: self.name.clone(),
+        })
+        .init_resource::<CoopSession>()
+        .add_startup_system(Self::connect)
+        .add_system(Self::receive_messages)
+        .add_system(Self::apply_updates)
+        .add_system(Self::forward_moves)
+        .add_system(Self::display_cursors);
+        add_net_board_event(app);
+        log::info!("Loaded Co-op Client Plugin");
+    }
+}
+
+impl CoopClientPlugin {
+    fn connect(mut commands: Commands, config: Res<CoopClientConfig>) {
+        let connection = Connection::connect(&config.address)
+            .map(|c| c.with_max_message_length(MAX_SYNC_MESSAGE_LENGTH));
+        let mut connection = match connection {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!(
+                    "Failed to connect to co-op server {}: {}",
+                    config.address,
+                    e
+                );
+                return;
+            }
+        };
+        let join = ClientMessage::Join {
+            name: config.name.clone(),
+        };
+        if let Err(e) = connection.send(&join) {
+            log::error!("Failed to join co-op game: {}", e);
+            return;
+        }
+        log::info!("Connected to co-op server {}", config.address);
+        commands.insert_resource(CoopConnection(connection));
+    }
+
+    fn receive_messages(
+        mut commands: Commands,
+        connection: Option<ResMut<CoopConnection>>,
+        mut session: ResMut<CoopSession>,
+        mut net_board_ewr: EventWriter<NetBoardEvent>,
+    ) {
+        let mut connection = match connection {
+            Some(c) => c,
+            None => return,
+        };
+        let messages = match connection.0.poll::<SyncMessage>() {
+            Ok(m) => m,
+            Err(e) => {
+                log::error!("Co-op server connection error: {}", e);
+                Vec::new()
+            }
+        };
+        for message in messages {
+            match message {
+                Ok(SyncMessage::Welcome { player }) => {
+                    log::info!("Joined co-op game as player {}", player);
+                    session.player = Some(player);
+                }
+                Ok(SyncMessage::Snapshot {
+                    options,
+                    uncovered,
+                    marks,
+                    cursors,
+                }) => {
+                    log::info!("Received co-op board with seed {:?}", options.seed);
+                    // The snapshot applies to the board spawned from these options
+                    session.sync.reset(uncovered, marks);
+                    session.cursors = cursors.into_iter().collect();
+                    net_board_ewr.send(NetBoardEvent(options));
+                }
+                Ok(SyncMessage::Cursor { player, coords }) => match coords {
+                    Some(coords) => {
+                        session.cursors.insert(player, coords);
+                    }
+                    None => {
+                        session.cursors.remove(&player);
+                    }
+                },
+                Ok(SyncMessage::Rejected { reason }) => {
+                    log::warn!("Co-op server rejected a move: {}", reason);
+                }
+                Ok(update) => session.sync.push(update),
+                Err(e) => log::error!("Malformed co-op server message: {}", e),
+            }
+        }
+        if connection.0.is_closed() {
+            log::warn!("Co-op server closed the connection");
+            commands.remove_resource::<CoopConnection>();
+        }
+    }
+
+    /// Applies the server updates to the shared board
+    fn apply_updates(
+        mut commands: Commands,
+        mut session: ResMut<CoopSession>,
+        mut boards: Query<&mut Board>,
+        new_boards: Query<Entity, Added<Board>>,
+        children: Query<&Children>,
+        board_assets: Res<BoardAssets>,
+    ) {
+        session.sync.apply(
+            &mut commands,
+            RemoteBoard,
+            &mut boards,
+            &new_boards,
+            &children,
+            &board_assets,
+            true,
+        );
+    }
+
+    /// Forwards our moves and hovered tile to the server
+    fn forward_moves(
+        connection: Option<ResMut<CoopConnection>>,
+        mut session: ResMut<CoopSession>,
+        windows: Res<Windows>,
+        boards: Query<&Board>,
+        mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+        mut tile_chord_evr: EventReader<TileChordEvent>,
+        mut tile_mark_evr: EventReader<TileMarkEvent>,
+    ) {
+        let mut connection = match connection {
+            Some(c) => c,
+            None => return,
+        };
+        let board_entity = match session.board() {
+            Some(b) => b,
+            None => return,
+        };
+        let mut messages: Vec<ClientMessage> = tile_trigger_evr
+            .iter()
+            .filter(|e| e.board == board_entity)
+            .map(|e| ClientMessage::Uncover(e.coords))
+            .chain(
+                tile_chord_evr
+                    .iter()
+                    .filter(|e| e.board == board_entity)
+                    .map(|e| ClientMessage::Chord(e.coords)),
+            )
+            .chain(
+                tile_mark_evr
+                    .iter()
+                    .filter(|e| e.board == board_entity)
+                    .map(|e| ClientMessage::Mark(e.coords)),
+            )
+            .collect();
+        let hovered = match (windows.get_primary(), boards.get(board_entity)) {
+            (Some(window), Ok(board)) => window
+                .cursor_position()
+                .and_then(|pos| board.mouse_position(window, pos)),
+            _ => None,
+        };
+        if hovered != session.hovered {
+            session.hovered = hovered;
+            messages.push(ClientMessage::Cursor(hovered));
+        }
+        for message in messages {
+            if let Err(e) = connection.0.send(&message) {
+                log::error!("Failed to send move to co-op server: {}", e);
+            }
+        }
+    }
+
+    /// Shows the other players' cursors over the shared board
+    fn display_cursors(
+        mut commands: Commands,
+        session: Res<CoopSession>,
+        boards: Query<&Board>,
+        board_assets: Res<BoardAssets>,
+        mut cursors: Query<(Entity, &CoopCursor, &mut Transform, &mut Sprite)>,
+    ) {
+        let board = match session.board().and_then(|e| boards.get(e).ok()) {
+            Some(b) => b,
+            None => return,
+        };
+        let position = |coords: &Coordinates| {
+            let tile = Vec2::new(coords.x as f32, coords.y as f32) * board.tile_size;
+            (board.bounds.position + tile + board.tile_size / 2.).extend(10.)
+        };
+        let mut displayed = Vec::new();
+        for (entity, cursor, mut transform, mut sprite) in cursors.iter_mut() {
+            match session.cursors.get(&cursor.0) {
+                Some(coords) if session.player != Some(cursor.0) => {
+                    transform.translation = position(coords);
+                    sprite.custom_size = Some(Vec2::splat(board.tile_size));
+                    displayed.push(cursor.0);
+                }
+                _ => commands.entity(entity).despawn_recursive(),
+            }
+        }
+        for (player, coords) in session.cursors.iter() {
+            if displayed.contains(player) || session.player == Some(*player) {
+                continue;
+            }
+            let mut color = board_assets.player_color(*player as usize);
+            color.set_a(0.5);
+            commands
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(board.tile_size)),
+                        color,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation(position(coords)),
+                    ..Default::default()
+                })
+                .insert(CoopCursor(*player))
+                .insert(Name::new(format!("Player {} cursor", player + 1)));
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::components::Coordinates;
+use crate::net::{ClientMessage, Connection, PlayerId, ServerBoard, SyncMessage};
+use crate::BoardOptions;
+use bevy::log;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+
+#[derive(Debug)]
+struct CoopPlayer {
+    id: PlayerId,
+    connection: Connection,
+    joined: bool,
+    cursor: Option<Coordinates>,
+}
+
+/// Authoritative co-op server, every player plays on the same board.
+///
+/// Conflicts are resolved as follows:
+/// - moves are applied in the order the server receives them
+/// - a flag belongs to the player who placed it, only its owner can remove it
+/// - flagged tiles can't be uncovered, and chording respects every player's flags
+#[derive(Debug)]
+pub struct CoopServer {
+    listener: TcpListener,
+    options: BoardOptions,
+    board: ServerBoard,
+    players: Vec<CoopPlayer>,
+    next_id: PlayerId,
+}
+
+impl CoopServer {
+    /// Creates a server listening on `address`, use port 0 to pick a free port.
+    ///
+    /// A random seed is picked if `options` have none
+    pub fn bind(address: impl ToSocketAddrs, mut options: BoardOptions) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        options.seed.get_or_insert_with(rand::random);
+        Ok(Self {
+            listener,
+            board: ServerBoard::new(&options),
+            options,
+            players: Vec::new(),
+            next_id: 0,
+        })
+    }
+
+    /// Address the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// The shared board
+    #[inline]
+    #[must_use]
+    pub fn board(&self) -> &ServerBoard {
+        &self.board
+    }
+
+    /// Accepts new players and handles every received message, without blocking
+    pub fn step(&mut self) -> io::Result<()> {
+        self.accept()?;
+        for index in 0..self.players.len() {
+            let messages = match self.players[index].connection.poll::<ClientMessage>() {
+                Ok(m) => m,
+                Err(e) => {
+                    log::warn!("Player {} connection error: {}", self.players[index].id, e);
+                    continue;
+                }
+            };
+            for message in messages {
+                match message {
+                    Ok(message) => self.handle(index, message),
+                    Err(e) => self.reject(index, format!("malformed message: {}", e)),
+                }
+            }
+        }
+        // Players leaving the board take their cursor with them
+        let left: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|p| p.connection.is_closed())
+            .map(|p| p.id)
+            .collect();
+        self.players.retain(|p| !p.connection.is_closed());
+        for player in left {
+            log::info!("Player {} left", player);
+            self.broadcast(&SyncMessage::Cursor {
+                player,
+                coords: None,
+            });
+        }
+        for player in self.players.iter_mut() {
+            if let Err(e) = player.connection.flush() {
+                log::warn!("Failed to send to player {}: {}", player.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn accept(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    // A broken socket only loses its own player
+                    let connection = match Connection::new(stream) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            log::warn!("Failed to accept player {}: {}", address, e);
+                            continue;
+                        }
+                    };
+                    let player = CoopPlayer {
+                        id: self.next_id,
+                        connection,
+                        joined: false,
+                        cursor: None,
+                    };
+                    log::info!("Player {} connected from {}", player.id, address);
+                    self.next_id = self.next_id.wrapping_add(1);
+                    self.players.push(player);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn handle(&mut self, index: usize, message: ClientMessage) {
+        let id = self.players[index].id;
+        if let ClientMessage::Join { name } = message {
+            if !self.players[index].joined {
+                log::info!("Player {} joined as {}", id, name);
+                self.players[index].joined = true;
+                let snapshot = self.snapshot();
+                self.send(index, &SyncMessage::Welcome { player: id });
+                self.send(index, &snapshot);
+            }
+            return;
+        }
+        if !self.players[index].joined {
+            self.reject(index, "join the game first".to_string());
+            return;
+        }
+        let update = match message {
+            ClientMessage::Join { .. } => return,
+            ClientMessage::Cursor(coords) => {
+                self.players[index].cursor = coords;
+                Ok(SyncMessage::Cursor { player: id, coords })
+            }
+            ClientMessage::Uncover(coords) => {
+                self.board.uncover(coords).map(SyncMessage::Uncovered)
+            }
+            ClientMessage::Chord(coords) => self.board.chord(coords).map(SyncMessage::Uncovered),
+            ClientMessage::Mark(coords) => self
+                .board
+                .toggle_mark(coords, id)
+                .map(|owner| SyncMessage::Marked { coords, owner }),
+        };
+        match update {
+            Ok(SyncMessage::Uncovered(tiles)) if tiles.is_empty() => (),
+            Ok(update) => self.broadcast(&update),
+            Err(e) => self.reject(index, e.to_string()),
+        }
+    }
+
+    fn snapshot(&self) -> SyncMessage {
+        SyncMessage::Snapshot {
+            options: self.options.clone(),
+            uncovered: self.board.game().uncovered().collect(),
+            marks: self.board.marks().collect(),
+            cursors: self
+                .players
+                .iter()
+                .filter_map(|p| p.cursor.map(|c| (p.id, c)))
+                .collect(),
+        }
+    }
+
+    fn send(&mut self, index: usize, message: &SyncMessage) {
+        let player = &mut self.players[index];
+        if let Err(e) = player.connection.send(message) {
+            log::warn!("Failed to send to player {}: {}", player.id, e);
+        }
+    }
+
+    fn reject(&mut self, index: usize, reason: String) {
+        log::debug!(
+            "Rejected player {} message: {}",
+            self.players[index].id,
+            reason
+        );
+        self.send(index, &SyncMessage::Rejected { reason });
+    }
+
+    fn broadcast(&mut self, message: &SyncMessage) {
+        for player in self.players.iter_mut().filter(|p| p.joined) {
+            if let Err(e) = player.connection.send(message) {
+                log::warn!("Failed to send to player {}: {}", player.id, e);
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Networked game modes: authoritative servers and client plugins exchanging newline delimited
+//! JSON messages over TCP.
+//!
+//! - Versus race: every player clears their own copy of the same seeded board
+//! - Co-op: every player plays on a single shared board
+//! - Spectator stream: a game is published for read only spectators
+pub(crate) use board_sync::*;
+pub use {
+    coop_client::*, coop_server::*, protocol::*, race_client::*, race_server::*, server_board::*,
+    spectator::*,
+};
+
+mod board_sync;
+mod coop_client;
+mod coop_server;
+mod protocol;
+mod race_client;
+mod race_server;
+mod server_board;
+mod spectator;
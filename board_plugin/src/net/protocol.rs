@@ -0,0 +1,214 @@
+use crate::components::Coordinates;
+use crate::BoardOptions;
+use bevy::app::{App, Events};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::TcpStream;
+
+/// Default race server address
+pub const DEFAULT_RACE_ADDRESS: &str = "127.0.0.1:7878";
+/// Default co-op server address
+pub const DEFAULT_COOP_ADDRESS: &str = "127.0.0.1:7879";
+/// Default spectator stream address
+pub const DEFAULT_SPECTATOR_ADDRESS: &str = "127.0.0.1:7880";
+/// Longest message accepted by default, client messages are a few bytes
+pub const MAX_MESSAGE_LENGTH: usize = 64 * 1024;
+/// Longest message accepted from a board server, snapshots of large boards list many tiles
+pub const MAX_SYNC_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Sent when a server provides the board to play, carrying its seeded options
+#[derive(Debug, Clone)]
+pub struct NetBoardEvent(pub BoardOptions);
+
+/// Registers `NetBoardEvent` once, as several net plugins may send it
+pub(crate) fn add_net_board_event(app: &mut App) {
+    if !app.world.contains_resource::<Events<NetBoardEvent>>() {
+        app.add_event::<NetBoardEvent>();
+    }
+}
+
+/// Message sent by a client to a race or co-op server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// Joins the game
+    Join { name: String },
+    /// Hovered tile, shown to the other co-op players
+    Cursor(Option<Coordinates>),
+    /// Uncovers a tile
+    Uncover(Coordinates),
+    /// Uncovers the neighbors of a tile
+    Chord(Coordinates),
+    /// Marks or unmarks a tile
+    Mark(Coordinates),
+}
+
+/// Player id given by the servers, wide enough to never wrap onto a connected player
+pub type PlayerId = u32;
+
+/// Progress of a single player in a race
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerProgress {
+    /// Player id
+    pub player: PlayerId,
+    /// Player name
+    pub name: String,
+    /// Percentage of safe tiles uncovered
+    pub cleared: f32,
+    /// Uncovered bombs
+    pub explosions: u16,
+    /// Seconds from the race start to the board completion
+    pub finish_time: Option<f32>,
+}
+
+/// Message sent by the race server to its clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// The client joined the race as `player`
+    Welcome { player: PlayerId },
+    /// The race starts, every player gets the same seeded board
+    Start { options: BoardOptions },
+    /// Progress of every player
+    Progress(Vec<PlayerProgress>),
+    /// The last client message was refused
+    Rejected { reason: String },
+}
+
+/// Board synchronization message sent by the co-op server and the spectator stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SyncMessage {
+    /// The client joined the game as `player`
+    Welcome { player: PlayerId },
+    /// Full board state, sent when joining
+    Snapshot {
+        options: BoardOptions,
+        uncovered: Vec<Coordinates>,
+        marks: Vec<(Coordinates, PlayerId)>,
+        cursors: Vec<(PlayerId, Coordinates)>,
+    },
+    /// Tiles uncovered by a move
+    Uncovered(Vec<Coordinates>),
+    /// A tile was marked by `owner`, or unmarked
+    Marked {
+        coords: Coordinates,
+        owner: Option<PlayerId>,
+    },
+    /// A player hovered a tile, or left the board
+    Cursor {
+        player: PlayerId,
+        coords: Option<Coordinates>,
+    },
+    /// The last client message was refused
+    Rejected { reason: String },
+}
+
+/// Non blocking TCP connection exchanging newline delimited JSON messages
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+    read_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+    max_message_length: usize,
+    closed: bool,
+}
+
+impl Connection {
+    /// Wraps a connected stream, switching it to non blocking mode
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nonblocking(true)?;
+        stream.set_nodelay(true)?;
+        Ok(Self {
+            stream,
+            read_buffer: Vec::new(),
+            write_buffer: Vec::new(),
+            max_message_length: MAX_MESSAGE_LENGTH,
+            closed: false,
+        })
+    }
+
+    /// Sets the longest message accepted from the peer, `MAX_MESSAGE_LENGTH` by default
+    #[must_use]
+    pub fn with_max_message_length(mut self, max_message_length: usize) -> Self {
+        self.max_message_length = max_message_length;
+        self
+    }
+
+    /// Connects to `address`
+    pub fn connect(address: &str) -> io::Result<Self> {
+        Self::new(TcpStream::connect(address)?)
+    }
+
+    /// Has the peer closed the connection
+    #[inline]
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Queues a message and sends as much as possible
+    pub fn send<M: Serialize>(&mut self, message: &M) -> io::Result<()> {
+        serde_json::to_writer(&mut self.write_buffer, message)?;
+        self.write_buffer.push(b'\n');
+        self.flush()
+    }
+
+    /// Sends the queued messages without blocking
+    pub fn flush(&mut self) -> io::Result<()> {
+        while !self.write_buffer.is_empty() {
+            match self.stream.write(&self.write_buffer) {
+                Ok(0) => {
+                    self.closed = true;
+                    return Err(ErrorKind::WriteZero.into());
+                }
+                Ok(n) => {
+                    self.write_buffer.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.closed = true;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieves every complete message received so far without blocking.
+    ///
+    /// Malformed messages are returned as errors and skipped, a message longer than the maximum
+    /// length closes the connection
+    pub fn poll<M: DeserializeOwned>(&mut self) -> io::Result<Vec<io::Result<M>>> {
+        let mut chunk = [0; 4096];
+        let mut messages = Vec::new();
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    self.closed = true;
+                    break;
+                }
+                Ok(n) => self.read_buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.closed = true;
+                    return Err(e);
+                }
+            }
+            // Complete messages are parsed right away so only the pending one is buffered
+            while let Some(end) = self.read_buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.read_buffer.drain(..=end).collect();
+                messages.push(serde_json::from_slice(&line).map_err(io::Error::from));
+            }
+            if self.read_buffer.len() > self.max_message_length {
+                self.closed = true;
+                self.read_buffer.clear();
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("message longer than {} bytes", self.max_message_length),
+                ));
+            }
+        }
+        Ok(messages)
+    }
+}
@@ -0,0 +1,137 @@
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+use crate::net::{
+    add_net_board_event, ClientMessage, Connection, NetBoardEvent, PlayerId, PlayerProgress,
+    ServerMessage,
+};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Versus race client plugin.
+///
+/// Joins the race server at `address`, forwards every board move to it and keeps the
+/// `RaceStandings` up to date. The app is responsible for spawning the board on `NetBoardEvent`
+pub struct RaceClientPlugin {
+    /// Race server address
+    pub address: String,
+    /// Player name
+    pub name: String,
+}
+
+/// Race progress of every player. Must be used as a resource.
+#[derive(Debug, Clone, Default)]
+pub struct RaceStandings {
+    /// Our player id, once the server welcomed us
+    pub player: Option<PlayerId>,
+    /// Progress of every player
+    pub progress: Vec<PlayerProgress>,
+}
+
+/// Connection to the race server
+struct RaceConnection(Connection);
+
+struct RaceClientConfig {
+    address: String,
+    name: String,
+}
+
+impl Plugin for RaceClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RaceClientConfig {
+            address: self.address.clone(),
+            name: self.name.clone(),
+        })
+        .init_resource::<RaceStandings>()
+        .add_startup_system(Self::connect)
+        .add_system(Self::receive_messages)
+        .add_system(Self::forward_moves);
+        add_net_board_event(app);
+        log::info!("Loaded Race Client Plugin");
+    }
+}
+
+impl RaceClientPlugin {
+    fn connect(mut commands: Commands, config: Res<RaceClientConfig>) {
+        let mut connection = match Connection::connect(&config.address) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to connect to race server {}: {}", config.address, e);
+                return;
+            }
+        };
+        let join = ClientMessage::Join {
+            name: config.name.clone(),
+        };
+        if let Err(e) = connection.send(&join) {
+            log::error!("Failed to join race: {}", e);
+            return;
+        }
+        log::info!("Connected to race server {}", config.address);
+        commands.insert_resource(RaceConnection(connection));
+    }
+
+    fn receive_messages(
+        mut commands: Commands,
+        connection: Option<ResMut<RaceConnection>>,
+        mut standings: ResMut<RaceStandings>,
+        mut net_board_ewr: EventWriter<NetBoardEvent>,
+    ) {
+        let mut connection = match connection {
+            Some(c) => c,
+            None => return,
+        };
+        let messages = match connection.0.poll::<ServerMessage>() {
+            Ok(m) => m,
+            Err(e) => {
+                log::error!("Race server connection error: {}", e);
+                Vec::new()
+            }
+        };
+        for message in messages {
+            match message {
+                Ok(ServerMessage::Welcome { player }) => {
+                    log::info!("Joined race as player {}", player);
+                    standings.player = Some(player);
+                }
+                Ok(ServerMessage::Start { options }) => {
+                    log::info!("Race started with seed {:?}", options.seed);
+                    net_board_ewr.send(NetBoardEvent(options));
+                }
+                Ok(ServerMessage::Progress(progress)) => standings.progress = progress,
+                Ok(ServerMessage::Rejected { reason }) => {
+                    log::warn!("Race server rejected a message: {}", reason);
+                }
+                Err(e) => log::error!("Malformed race server message: {}", e),
+            }
+        }
+        if connection.0.is_closed() {
+            log::warn!("Race server closed the connection");
+            commands.remove_resource::<RaceConnection>();
+        }
+    }
+
+    fn forward_moves(
+        connection: Option<ResMut<RaceConnection>>,
+        mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+        mut tile_chord_evr: EventReader<TileChordEvent>,
+        mut tile_mark_evr: EventReader<TileMarkEvent>,
+    ) {
+        let mut connection = match connection {
+            Some(c) => c,
+            None => return,
+        };
+        let moves = tile_trigger_evr
+            .iter()
+            .map(|e| ClientMessage::Uncover(e.coords))
+            .chain(
+                tile_chord_evr
+                    .iter()
+                    .map(|e| ClientMessage::Chord(e.coords)),
+            )
+            .chain(tile_mark_evr.iter().map(|e| ClientMessage::Mark(e.coords)));
+        for message in moves {
+            if let Err(e) = connection.0.send(&message) {
+                log::error!("Failed to send move to race server: {}", e);
+            }
+        }
+    }
+}
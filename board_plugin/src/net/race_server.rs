@@ -0,0 +1,235 @@
+use crate::net::{ClientMessage, Connection, PlayerId, PlayerProgress, ServerMessage};
+use crate::BoardOptions;
+use bevy::log;
+use minesweeper_core::Game;
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::time::Instant;
+
+/// Race server configuration
+#[derive(Debug, Clone)]
+pub struct RaceConfig {
+    /// Number of players the race waits for before starting
+    pub players: usize,
+    /// Board options shared by every player, a random seed is picked if none is set
+    pub options: BoardOptions,
+}
+
+#[derive(Debug)]
+struct RacePlayer {
+    id: PlayerId,
+    connection: Connection,
+    name: Option<String>,
+    board: Option<Game>,
+    finish_time: Option<f32>,
+}
+
+/// Authoritative versus race server.
+///
+/// Players join, get the same seeded board once enough of them are connected, and their moves are
+/// replayed on the server to broadcast everyone's progress
+#[derive(Debug)]
+pub struct RaceServer {
+    listener: TcpListener,
+    config: RaceConfig,
+    players: Vec<RacePlayer>,
+    next_id: PlayerId,
+    start: Option<Instant>,
+}
+
+impl RaceServer {
+    /// Creates a server listening on `address`, use port 0 to pick a free port
+    pub fn bind(address: impl ToSocketAddrs, mut config: RaceConfig) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        listener.set_nonblocking(true)?;
+        config.options.seed.get_or_insert_with(rand::random);
+        Ok(Self {
+            listener,
+            config,
+            players: Vec::new(),
+            next_id: 0,
+            start: None,
+        })
+    }
+
+    /// Address the server listens on
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Has the race started
+    #[inline]
+    #[must_use]
+    pub fn is_started(&self) -> bool {
+        self.start.is_some()
+    }
+
+    /// Accepts new players and handles every received message, without blocking
+    pub fn step(&mut self) -> io::Result<()> {
+        self.accept()?;
+        let mut progressed = false;
+        for id in 0..self.players.len() {
+            let messages = match self.players[id].connection.poll::<ClientMessage>() {
+                Ok(m) => m,
+                Err(e) => {
+                    log::warn!("Player {} connection error: {}", self.players[id].id, e);
+                    continue;
+                }
+            };
+            for message in messages {
+                match message {
+                    Ok(message) => progressed |= self.handle(id, message),
+                    Err(e) => self.reject(id, format!("malformed message: {}", e)),
+                }
+            }
+        }
+        self.players.retain(|p| !p.connection.is_closed());
+        if !self.is_started()
+            && self.players.len() >= self.config.players
+            && self.players.iter().all(|p| p.name.is_some())
+        {
+            self.start_race();
+            progressed = true;
+        }
+        if progressed {
+            let progress = self.progress();
+            self.broadcast(&ServerMessage::Progress(progress));
+        }
+        for player in self.players.iter_mut() {
+            if let Err(e) = player.connection.flush() {
+                log::warn!("Failed to send to player: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn accept(&mut self) -> io::Result<()> {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, address)) => {
+                    // A broken socket only loses its own player
+                    let connection = match Connection::new(stream) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            log::warn!("Failed to accept player {}: {}", address, e);
+                            continue;
+                        }
+                    };
+                    let mut player = RacePlayer {
+                        id: self.next_id,
+                        connection,
+                        name: None,
+                        board: None,
+                        finish_time: None,
+                    };
+                    if self.is_started() {
+                        log::info!("Refused late player from {}", address);
+                        let reason = "the race already started".to_string();
+                        if let Err(e) = player.connection.send(&ServerMessage::Rejected { reason })
+                        {
+                            log::warn!("Failed to refuse {}: {}", address, e);
+                        }
+                        continue;
+                    }
+                    log::info!("Player {} connected from {}", player.id, address);
+                    self.next_id = self.next_id.wrapping_add(1);
+                    self.players.push(player);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Handles a message, returning if the progress changed
+    fn handle(&mut self, id: usize, message: ClientMessage) -> bool {
+        let start = self.start;
+        let player = &mut self.players[id];
+        let (result, coords) = match (message, player.board.as_mut()) {
+            (ClientMessage::Join { name }, _) => {
+                if player.name.is_none() {
+                    log::info!("Player {} joined as {}", player.id, name);
+                    player.name = Some(name);
+                    let welcome = ServerMessage::Welcome { player: player.id };
+                    if let Err(e) = player.connection.send(&welcome) {
+                        log::warn!("Failed to welcome player {}: {}", player.id, e);
+                    }
+                }
+                return false;
+            }
+            // Cursors are only shared in co-op
+            (ClientMessage::Cursor(_), _) => return false,
+            (_, None) => {
+                self.reject(id, "the race did not start".to_string());
+                return false;
+            }
+            (ClientMessage::Uncover(coords), Some(board)) => {
+                (board.reveal(coords).map(|_| ()), coords)
+            }
+            (ClientMessage::Chord(coords), Some(board)) => {
+                (board.chord(coords).map(|_| ()), coords)
+            }
+            (ClientMessage::Mark(coords), Some(board)) => {
+                (board.toggle_flag(coords).map(|_| ()), coords)
+            }
+        };
+        if let Err(e) = result {
+            self.reject(id, e.to_string());
+            return false;
+        }
+        let player = &mut self.players[id];
+        let completed = player.board.as_ref().is_some_and(Game::is_completed);
+        if completed && player.finish_time.is_none() {
+            let time = start.map_or(0., |s| s.elapsed().as_secs_f32());
+            log::info!("Player {} finished in {:.2}s ({})", player.id, time, coords);
+            player.finish_time = Some(time);
+        }
+        true
+    }
+
+    fn reject(&mut self, id: usize, reason: String) {
+        let player = &mut self.players[id];
+        log::debug!("Rejected player {} message: {}", player.id, reason);
+        if let Err(e) = player.connection.send(&ServerMessage::Rejected { reason }) {
+            log::warn!("Failed to send to player {}: {}", player.id, e);
+        }
+    }
+
+    fn start_race(&mut self) {
+        log::info!(
+            "Starting race for {} players with seed {:?}",
+            self.players.len(),
+            self.config.options.seed
+        );
+        for player in self.players.iter_mut() {
+            player.board = Some(Game::new(&self.config.options.game_options()));
+        }
+        let options = self.config.options.clone();
+        self.broadcast(&ServerMessage::Start { options });
+        self.start = Some(Instant::now());
+    }
+
+    fn progress(&self) -> Vec<PlayerProgress> {
+        self.players
+            .iter()
+            .map(|player| PlayerProgress {
+                player: player.id,
+                name: player.name.clone().unwrap_or_default(),
+                cleared: player.board.as_ref().map_or(0., Game::cleared),
+                explosions: player
+                    .board
+                    .as_ref()
+                    .map_or(0, |b| b.explosions().len() as u16),
+                finish_time: player.finish_time,
+            })
+            .collect()
+    }
+
+    fn broadcast(&mut self, message: &ServerMessage) {
+        for player in self.players.iter_mut() {
+            if let Err(e) = player.connection.send(message) {
+                log::warn!("Failed to send to player: {}", e);
+            }
+        }
+    }
+}
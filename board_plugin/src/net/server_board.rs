@@ -0,0 +1,112 @@
+use crate::components::Coordinates;
+use crate::net::PlayerId;
+use crate::BoardOptions;
+use minesweeper_core::{Game, MoveError};
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Reason for refusing a move on a shared board
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SharedMoveError {
+    /// The move breaks the game rules
+    Move(MoveError),
+    /// The tile flag belongs to another player
+    NotOwner {
+        coords: Coordinates,
+        owner: PlayerId,
+    },
+}
+
+impl From<MoveError> for SharedMoveError {
+    fn from(error: MoveError) -> Self {
+        Self::Move(error)
+    }
+}
+
+impl Display for SharedMoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Move(error) => error.fmt(f),
+            Self::NotOwner { coords, owner } => {
+                write!(f, "the flag on {} belongs to player {}", coords, owner)
+            }
+        }
+    }
+}
+
+/// Authoritative server side board shared by several players.
+///
+/// Flags belong to the player who placed them and only their owner can remove them
+#[derive(Debug, Clone)]
+pub struct ServerBoard {
+    game: Game,
+    owners: HashMap<Coordinates, PlayerId>,
+}
+
+impl ServerBoard {
+    /// Generates the board matching `options`, exactly as the clients do
+    #[must_use]
+    pub fn new(options: &BoardOptions) -> Self {
+        Self {
+            game: Game::new(&options.game_options()),
+            owners: HashMap::new(),
+        }
+    }
+
+    /// Forgets the owners of the flags removed by uncovering `revealed`
+    fn clear_owners(&mut self, revealed: &[Coordinates]) {
+        for coords in revealed {
+            self.owners.remove(coords);
+        }
+    }
+
+    /// Uncovers an unmarked tile, returning the uncovered tiles
+    pub fn uncover(&mut self, coords: Coordinates) -> Result<Vec<Coordinates>, SharedMoveError> {
+        let revealed = self.game.reveal(coords)?;
+        self.clear_owners(&revealed);
+        Ok(revealed)
+    }
+
+    /// Uncovers the unmarked neighbors of an uncovered bomb neighbor whose bombs are all marked,
+    /// returning the uncovered tiles
+    pub fn chord(&mut self, coords: Coordinates) -> Result<Vec<Coordinates>, SharedMoveError> {
+        let revealed = self.game.chord(coords)?;
+        self.clear_owners(&revealed);
+        Ok(revealed)
+    }
+
+    /// Marks or unmarks a covered tile for `player`, returning the new flag owner
+    pub fn toggle_mark(
+        &mut self,
+        coords: Coordinates,
+        player: PlayerId,
+    ) -> Result<Option<PlayerId>, SharedMoveError> {
+        if let Some(owner) = self.owners.get(&coords) {
+            if *owner != player {
+                return Err(SharedMoveError::NotOwner {
+                    coords,
+                    owner: *owner,
+                });
+            }
+        }
+        if self.game.toggle_flag(coords)? {
+            self.owners.insert(coords, player);
+            Ok(Some(player))
+        } else {
+            self.owners.remove(&coords);
+            Ok(None)
+        }
+    }
+
+    /// The shared game
+    #[inline]
+    #[must_use]
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Marked tiles and their owner
+    pub fn marks(&self) -> impl Iterator<Item = (Coordinates, PlayerId)> + '_ {
+        self.owners.iter().map(|(c, o)| (*c, *o))
+    }
+}
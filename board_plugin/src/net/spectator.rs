@@ -0,0 +1,268 @@
+use crate::components::{Coordinates, ReadOnlyBoard};
+use crate::net::{
+    add_net_board_event, BoardSync, Connection, NetBoardEvent, SyncMessage, MAX_SYNC_MESSAGE_LENGTH,
+};
+use crate::{Board, BoardAssets};
+use bevy::ecs::query::ChangeTrackers;
+use bevy::log;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::io::ErrorKind;
+use std::net::TcpListener;
+
+/// Spectator stream publisher plugin.
+///
+/// Publishes the local game on `address`: spectators receive a snapshot of the board when they
+/// connect, and the uncovered and marked tiles after every move. Only one board is published
+pub struct SpectatorPublisherPlugin {
+    /// Address spectators connect to
+    pub address: String,
+}
+
+/// Spectator client plugin.
+///
+/// Connects to a spectator stream at `address` and mirrors the published game on a read only
+/// board. The app is responsible for spawning the board on `NetBoardEvent`
+pub struct SpectatorClientPlugin {
+    /// Spectator stream address
+    pub address: String,
+}
+
+/// Published game state
+struct SpectatorStream {
+    listener: TcpListener,
+    spectators: Vec<Connection>,
+    board: Option<Entity>,
+    covered: HashSet<Coordinates>,
+    marked: HashSet<Coordinates>,
+}
+
+/// Connection to the spectator stream
+struct SpectatorConnection(Connection);
+
+struct SpectatorAddress(String);
+
+#[derive(Default)]
+struct SpectatorView(BoardSync);
+
+impl Plugin for SpectatorPublisherPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpectatorAddress(self.address.clone()))
+            .add_startup_system(Self::listen)
+            .add_system(Self::publish);
+        log::info!("Loaded Spectator Publisher Plugin");
+    }
+}
+
+impl SpectatorPublisherPlugin {
+    fn listen(mut commands: Commands, address: Res<SpectatorAddress>) {
+        let listener = match TcpListener::bind(&address.0) {
+            Ok(l) => l,
+            Err(e) => {
+                log::error!("Failed to publish spectator stream on {}: {}", address.0, e);
+                return;
+            }
+        };
+        if let Err(e) = listener.set_nonblocking(true) {
+            log::error!("Failed to publish spectator stream: {}", e);
+            return;
+        }
+        log::info!("Publishing spectator stream on {}", address.0);
+        commands.insert_resource(SpectatorStream {
+            listener,
+            spectators: Vec::new(),
+            board: None,
+            covered: HashSet::default(),
+            marked: HashSet::default(),
+        });
+    }
+
+    /// Sends the board changes to the spectators, and a snapshot to new spectators
+    fn publish(
+        stream: Option<ResMut<SpectatorStream>>,
+        boards: Query<(Entity, &Board, ChangeTrackers<Board>)>,
+    ) {
+        let mut stream = match stream {
+            Some(s) => s,
+            None => return,
+        };
+        let mut new_spectators = Vec::new();
+        loop {
+            match stream.listener.accept() {
+                Ok((tcp, address)) => match Connection::new(tcp) {
+                    Ok(c) => {
+                        log::info!("Spectator connected from {}", address);
+                        new_spectators.push(c);
+                    }
+                    Err(e) => log::warn!("Failed to accept spectator {}: {}", address, e),
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) => {
+                    log::error!("Spectator stream error: {}", e);
+                    break;
+                }
+            }
+        }
+        // A new board is published once the previous one is gone
+        let published = stream.board.and_then(|e| boards.get(e).ok());
+        let (entity, board, tracker) = match published.or_else(|| boards.iter().next()) {
+            Some(b) => b,
+            None => {
+                stream.board = None;
+                stream.spectators.append(&mut new_spectators);
+                return;
+            }
+        };
+        if stream.board != Some(entity) {
+            stream.board = Some(entity);
+            stream.covered = board.game.covered().collect();
+            stream.marked = board.game.flags().collect();
+            let snapshot = Self::snapshot(board);
+            stream.broadcast(&snapshot);
+        } else if tracker.is_changed() {
+            let uncovered: Vec<Coordinates> = stream
+                .covered
+                .iter()
+                .filter(|c| !board.game.is_covered(**c))
+                .copied()
+                .collect();
+            let marked: HashSet<Coordinates> = board.game.flags().collect();
+            let mut updates: Vec<SyncMessage> = marked
+                .symmetric_difference(&stream.marked)
+                .map(|coords| SyncMessage::Marked {
+                    coords: *coords,
+                    owner: marked.contains(coords).then_some(0),
+                })
+                .collect();
+            if !uncovered.is_empty() {
+                for coords in uncovered.iter() {
+                    stream.covered.remove(coords);
+                }
+                updates.push(SyncMessage::Uncovered(uncovered));
+            }
+            stream.marked = marked;
+            for update in updates {
+                stream.broadcast(&update);
+            }
+        }
+        if !new_spectators.is_empty() {
+            let snapshot = Self::snapshot(board);
+            for mut spectator in new_spectators {
+                if let Err(e) = spectator.send(&snapshot) {
+                    log::warn!("Failed to send snapshot to spectator: {}", e);
+                }
+                stream.spectators.push(spectator);
+            }
+        }
+        for spectator in stream.spectators.iter_mut() {
+            if let Err(e) = spectator.flush() {
+                log::warn!("Failed to send to spectator: {}", e);
+            }
+        }
+        stream.spectators.retain(|s| !s.is_closed());
+    }
+
+    fn snapshot(board: &Board) -> SyncMessage {
+        SyncMessage::Snapshot {
+            options: board.options.clone(),
+            uncovered: board.game.uncovered().collect(),
+            marks: board.game.flags().map(|c| (c, 0)).collect(),
+            cursors: Vec::new(),
+        }
+    }
+}
+
+impl SpectatorStream {
+    fn broadcast(&mut self, message: &SyncMessage) {
+        for spectator in self.spectators.iter_mut() {
+            if let Err(e) = spectator.send(message) {
+                log::warn!("Failed to send to spectator: {}", e);
+            }
+        }
+    }
+}
+
+impl Plugin for SpectatorClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SpectatorAddress(self.address.clone()))
+            .init_resource::<SpectatorView>()
+            .add_startup_system(Self::connect)
+            .add_system(Self::receive_messages)
+            .add_system(Self::apply_updates);
+        add_net_board_event(app);
+        log::info!("Loaded Spectator Client Plugin");
+    }
+}
+
+impl SpectatorClientPlugin {
+    fn connect(mut commands: Commands, address: Res<SpectatorAddress>) {
+        let connection = Connection::connect(&address.0)
+            .map(|c| c.with_max_message_length(MAX_SYNC_MESSAGE_LENGTH));
+        match connection {
+            Ok(connection) => {
+                log::info!("Spectating {}", address.0);
+                commands.insert_resource(SpectatorConnection(connection));
+            }
+            Err(e) => log::error!("Failed to connect to spectator stream {}: {}", address.0, e),
+        }
+    }
+
+    fn receive_messages(
+        mut commands: Commands,
+        connection: Option<ResMut<SpectatorConnection>>,
+        mut view: ResMut<SpectatorView>,
+        mut net_board_ewr: EventWriter<NetBoardEvent>,
+    ) {
+        let mut connection = match connection {
+            Some(c) => c,
+            None => return,
+        };
+        let messages = match connection.0.poll::<SyncMessage>() {
+            Ok(m) => m,
+            Err(e) => {
+                log::error!("Spectator stream connection error: {}", e);
+                Vec::new()
+            }
+        };
+        for message in messages {
+            match message {
+                Ok(SyncMessage::Snapshot {
+                    options,
+                    uncovered,
+                    marks,
+                    ..
+                }) => {
+                    log::info!("Received spectated board with seed {:?}", options.seed);
+                    view.0.reset(uncovered, marks);
+                    net_board_ewr.send(NetBoardEvent(options));
+                }
+                Ok(update) => view.0.push(update),
+                Err(e) => log::error!("Malformed spectator stream message: {}", e),
+            }
+        }
+        if connection.0.is_closed() {
+            log::warn!("Spectator stream closed");
+            commands.remove_resource::<SpectatorConnection>();
+        }
+    }
+
+    /// Applies the published moves to the read only board
+    fn apply_updates(
+        mut commands: Commands,
+        mut view: ResMut<SpectatorView>,
+        mut boards: Query<&mut Board>,
+        new_boards: Query<Entity, Added<Board>>,
+        children: Query<&Children>,
+        board_assets: Res<BoardAssets>,
+    ) {
+        view.0.apply(
+            &mut commands,
+            ReadOnlyBoard,
+            &mut boards,
+            &new_boards,
+            &children,
+            &board_assets,
+            false,
+        );
+    }
+}
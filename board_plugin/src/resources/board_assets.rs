@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
+use serde::{Deserialize, Serialize};
 
 /// Material of a `Sprite` with a texture and color
 #[derive(Debug, Clone)]
@@ -17,6 +18,89 @@ impl Default for SpriteMaterial {
     }
 }
 
+/// Bomb counter color sets
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub enum ColorPalette {
+    #[default]
+    Default,
+    /// Red-green safe colors, for weak green perception
+    Deuteranopia,
+    /// Red-green safe colors, for weak red perception
+    Protanopia,
+    /// Blue-yellow safe colors
+    Tritanopia,
+    /// Saturated colors with distinct brightness
+    HighContrast,
+}
+
+impl ColorPalette {
+    /// Every palette, in settings order
+    pub const ALL: [Self; 5] = [
+        Self::Default,
+        Self::Deuteranopia,
+        Self::Protanopia,
+        Self::Tritanopia,
+        Self::HighContrast,
+    ];
+
+    /// Bomb counter colors of the palette, from 1 to 8
+    pub fn colors(self) -> Vec<Color> {
+        match self {
+            Self::Default => BoardAssets::default_colors(),
+            Self::Deuteranopia => vec![
+                Color::WHITE,
+                Color::rgb(0.34, 0.71, 0.91),
+                Color::rgb(0.9, 0.62, 0.),
+                Color::rgb(0.94, 0.89, 0.26),
+                Color::rgb(0.8, 0.47, 0.65),
+                Color::rgb(0.6, 0.85, 1.),
+                Color::rgb(1., 0.8, 0.5),
+                Color::rgb(0.7, 0.7, 0.7),
+            ],
+            Self::Protanopia => vec![
+                Color::WHITE,
+                Color::rgb(0.34, 0.71, 0.91),
+                Color::rgb(0.94, 0.89, 0.26),
+                Color::rgb(0.9, 0.62, 0.),
+                Color::rgb(0.6, 0.6, 1.),
+                Color::rgb(0.6, 0.85, 1.),
+                Color::rgb(1., 0.95, 0.6),
+                Color::rgb(0.7, 0.7, 0.7),
+            ],
+            Self::Tritanopia => vec![
+                Color::WHITE,
+                Color::rgb(0.3, 0.85, 0.9),
+                Color::rgb(1., 0.35, 0.35),
+                Color::rgb(1., 0.6, 0.75),
+                Color::rgb(0.6, 1., 0.95),
+                Color::rgb(0.85, 0.3, 0.6),
+                Color::rgb(1., 0.8, 0.8),
+                Color::rgb(0.7, 0.7, 0.7),
+            ],
+            Self::HighContrast => vec![
+                Color::WHITE,
+                Color::YELLOW,
+                Color::CYAN,
+                Color::rgb(1., 0.4, 1.),
+                Color::rgb(0.4, 1., 0.4),
+                Color::ORANGE,
+                Color::rgb(1., 0.3, 0.3),
+                Color::rgb(0.6, 0.6, 1.),
+            ],
+        }
+    }
+
+    /// Next palette in settings order
+    #[must_use]
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|p| *p == self)
+            .unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
 /// Assets for the board. Must be used as a resource.
 ///
 /// Use the loader for partial setup
@@ -24,20 +108,26 @@ impl Default for SpriteMaterial {
 pub struct BoardAssets {
     /// Label
     pub label: String,
-    ///
+    /// Board background
     pub board_material: SpriteMaterial,
-    ///
+    /// Uncovered tile
     pub tile_material: SpriteMaterial,
-    ///
+    /// Tile cover
     pub covered_tile_material: SpriteMaterial,
-    ///
+    /// Tile cover while held down by the mouse
+    pub pressed_tile_material: SpriteMaterial,
+    /// Bomb counter text font
     pub bomb_counter_font: Handle<Font>,
-    ///
+    /// Bomb counter text colors, from 1 to 8
     pub bomb_counter_colors: Vec<Color>,
-    ///
+    /// Adds a pip pattern next to every bomb counter, so counts can be told apart without color
+    pub counter_markers: bool,
+    /// Flag placed on marked tiles
     pub flag_material: SpriteMaterial,
-    ///
+    /// Bomb revealed under a tile
     pub bomb_material: SpriteMaterial,
+    /// Player colors, used for bombs claimed in flags mode
+    pub player_colors: Vec<Color>,
 }
 
 impl BoardAssets {
@@ -52,6 +142,19 @@ impl BoardAssets {
         ]
     }
 
+    /// Default player color set
+    pub fn default_player_colors() -> Vec<Color> {
+        vec![Color::RED, Color::BLUE, Color::GREEN, Color::YELLOW]
+    }
+
+    /// Safely retrieves the color matching a player
+    pub fn player_color(&self, player: usize) -> Color {
+        if self.player_colors.is_empty() {
+            return self.bomb_material.color;
+        }
+        self.player_colors[player % self.player_colors.len()]
+    }
+
     /// Safely retrieves the color matching a bomb counter
     pub fn bomb_counter_color(&self, counter: u8) -> Color {
         let counter = counter.saturating_sub(1) as usize;
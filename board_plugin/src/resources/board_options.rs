@@ -1,4 +1,5 @@
-use bevy::prelude::Vec3;
+use bevy::prelude::{Vec2, Vec3, Window};
+use minesweeper_core::{BoardCode, BoardLayout, Coordinates, GameOptions, GamePreset, TileMap};
 use serde::{Deserialize, Serialize};
 
 /// Tile size options
@@ -10,15 +11,62 @@ pub enum TileSize {
     Adaptive { min: f32, max: f32 },
 }
 
+/// Board anchor in the window area left free by the margins
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BoardAnchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
 /// Board position customization options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum BoardPosition {
-    /// Centered board
+    /// Board centered in the window area left free by the margins
     Centered { offset: Vec3 },
+    /// Board anchored in the window area left free by the margins
+    Anchored { anchor: BoardAnchor, offset: Vec3 },
     /// Custom position
     Custom(Vec3),
 }
 
+/// Window space reserved around the board for other UI, in pixels
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BoardMargins {
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+}
+
+/// Mouse trigger behaviour
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum TriggerMode {
+    /// Tiles are uncovered as soon as the mouse button goes down
+    Press,
+    /// Tiles show as pressed while the mouse button is held, and are uncovered on release.
+    /// Dragging off the tile before releasing cancels the action
+    #[default]
+    Release,
+}
+
+/// Game rules
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Single player, uncovering a bomb ends the game
+    #[default]
+    Classic,
+    /// Hot-seat competitive mode, players take turns and uncovering a bomb claims it.
+    /// There is no marking or chording, the player claiming the most bombs wins
+    Flags { players: u8 },
+}
+
 /// Board generation options. Must be used as a resource
 // We use serde to allow saving option presets and loading them at runtime
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,14 +77,60 @@ pub struct BoardOptions {
     pub bomb_count: u16,
     /// Baard world position
     pub position: BoardPosition,
+    /// Window space the board must leave free, used by adaptive tile size and anchored positions
+    pub margins: BoardMargins,
     /// Tile world size
     pub tile_size: TileSize,
     /// Padding between tiles
     pub tile_padding: f32,
     /// Does the board generate a safe place to start
     pub safe_start: bool,
+    /// Does uncovering happen on mouse press or release
+    pub trigger_mode: TriggerMode,
+    /// Does marking a flagged tile again leave a question mark before clearing it
+    pub question_marks: bool,
+    /// Bomb placement seed, boards with the same seed and options are identical.
+    /// A random board is generated if none is set
+    pub seed: Option<u64>,
+    /// Game rules
+    pub game_mode: GameMode,
+    /// Hand-made tile map to play instead of a generated one, its size and bombs replace
+    /// `map_size`, `bomb_count` and `seed`. Serialized as its rows
+    #[serde(default, with = "tile_map_rows")]
+    pub tile_map: Option<TileMap>,
+    /// Tile uncovered when the board starts, like the safe start of hand-made puzzles
+    #[serde(default)]
+    pub start: Option<Coordinates>,
 }
 
+/// Serializes hand-made tile maps as their rows, like puzzle files
+mod tile_map_rows {
+    use minesweeper_core::TileMap;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        tile_map: &Option<TileMap>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        tile_map.as_ref().map(TileMap::rows).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<TileMap>, D::Error> {
+        Option::<Vec<String>>::deserialize(deserializer)?
+            .map(|rows| TileMap::from_rows(&rows).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// Options for several boards displayed at once. Must be used as a resource.
+///
+/// When set, a board is spawned for every entry instead of using the `BoardOptions` resource
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BoardOptionsSet(pub Vec<BoardOptions>);
+
 impl Default for TileSize {
     fn default() -> Self {
         Self::Adaptive {
@@ -60,9 +154,109 @@ impl Default for BoardOptions {
             map_size: (15, 15),
             bomb_count: 30,
             position: Default::default(),
+            margins: Default::default(),
             tile_size: Default::default(),
             tile_padding: 0.,
             safe_start: false,
+            trigger_mode: Default::default(),
+            question_marks: false,
+            seed: None,
+            game_mode: Default::default(),
+            tile_map: None,
+            start: None,
         }
     }
 }
+
+impl BoardOptions {
+    /// Default display options with the rules of `preset`
+    #[must_use]
+    pub fn from_preset(preset: GamePreset) -> Self {
+        let options = preset.options();
+        Self {
+            map_size: options.map_size,
+            bomb_count: options.bomb_count,
+            safe_start: options.safe_start,
+            seed: options.seed,
+            ..Default::default()
+        }
+    }
+
+    /// Game rules options of the board
+    #[must_use]
+    pub fn game_options(&self) -> GameOptions {
+        GameOptions {
+            map_size: self.map_size,
+            bomb_count: self.bomb_count,
+            safe_start: self.safe_start,
+            seed: self.seed,
+        }
+    }
+
+    /// Sets the size, bombs, safe start and start cell of the board encoded by `code`
+    pub fn apply_code(&mut self, code: &BoardCode) {
+        self.map_size = code.map_size;
+        self.bomb_count = code.bomb_count;
+        self.safe_start = code.safe_start;
+        self.start = code.start;
+        match &code.layout {
+            BoardLayout::Seed(seed) => {
+                self.seed = Some(*seed);
+                self.tile_map = None;
+            }
+            BoardLayout::Bombs(_) => {
+                self.seed = None;
+                self.tile_map = Some(code.tile_map());
+            }
+        }
+    }
+
+    /// Window area left free by the margins, in world space (bottom left, size)
+    fn free_area(&self, window: &Window) -> (Vec2, Vec2) {
+        let window_size = Vec2::new(window.width(), window.height());
+        let size = Vec2::new(
+            window_size.x - self.margins.left - self.margins.right,
+            window_size.y - self.margins.top - self.margins.bottom,
+        )
+        .max(Vec2::ZERO);
+        let position = Vec2::new(self.margins.left, self.margins.bottom) - window_size / 2.;
+        (position, size)
+    }
+
+    /// Computes the tile world size for a tile map of the given size displayed in `window`
+    pub fn tile_size_for(&self, window: &Window, (width, height): (u16, u16)) -> f32 {
+        match self.tile_size {
+            TileSize::Fixed(v) => v,
+            TileSize::Adaptive { min, max } => {
+                let (_, area) = self.free_area(window);
+                let max_width = area.x / width as f32;
+                let max_heigth = area.y / height as f32;
+                max_width.min(max_heigth).clamp(min, max)
+            }
+        }
+    }
+
+    /// Computes the board anchor position (bottom left) for a board of `board_size` displayed in `window`
+    pub fn board_position(&self, window: &Window, board_size: Vec2) -> Vec3 {
+        let (anchor, offset) = match self.position {
+            BoardPosition::Centered { offset } => (BoardAnchor::Center, offset),
+            BoardPosition::Anchored { anchor, offset } => (anchor, offset),
+            BoardPosition::Custom(p) => return p,
+        };
+        let (area_position, area_size) = self.free_area(window);
+        let free_space = area_size - board_size;
+        // Fraction of the free space placed on the left and bottom of the board
+        let (x, y) = match anchor {
+            BoardAnchor::Center => (0.5, 0.5),
+            BoardAnchor::Top => (0.5, 1.),
+            BoardAnchor::Bottom => (0.5, 0.),
+            BoardAnchor::Left => (0., 0.5),
+            BoardAnchor::Right => (1., 0.5),
+            BoardAnchor::TopLeft => (0., 1.),
+            BoardAnchor::TopRight => (1., 1.),
+            BoardAnchor::BottomLeft => (0., 0.),
+            BoardAnchor::BottomRight => (1., 0.),
+        };
+        (area_position + free_space * Vec2::new(x, y)).extend(0.) + offset
+    }
+}
@@ -1,7 +1,5 @@
-pub use {board::*, board_assets::*, board_options::*};
+pub use {board_assets::*, board_options::*, tile_press::*};
 
-mod board;
 mod board_assets;
 mod board_options;
-pub(crate) mod tile;
-pub(crate) mod tile_map;
+mod tile_press;
@@ -0,0 +1,71 @@
+use crate::components::Coordinates;
+use bevy::prelude::*;
+
+/// Action performed by the held mouse buttons
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PressAction {
+    /// Left button, uncovers the tile
+    Uncover,
+    /// Middle button or both left and right buttons, uncovers the neighbors of the tile
+    Chord,
+}
+
+/// Mouse press state of the board. Must be used as a resource.
+///
+/// Tracks the held buttons and the tile they went down on, for press previews and release-to-act
+#[derive(Debug, Default)]
+pub struct TilePress {
+    /// Is the left mouse button held
+    pub left: bool,
+    /// Is the right mouse button held
+    pub right: bool,
+    /// Is the middle mouse button held
+    pub middle: bool,
+    /// Board and tile on which the press started
+    pub origin: Option<(Entity, Coordinates)>,
+    /// Was the press already acted upon or cancelled
+    pub consumed: bool,
+    /// Tile cover entities currently shown as pressed
+    pub pressed: Vec<Entity>,
+}
+
+impl TilePress {
+    /// Action matching the held buttons
+    #[must_use]
+    pub fn action(&self) -> Option<PressAction> {
+        if self.middle || (self.left && self.right) {
+            Some(PressAction::Chord)
+        } else if self.left {
+            Some(PressAction::Uncover)
+        } else {
+            None
+        }
+    }
+
+    /// The action, board and tile of a press which is still pending
+    #[must_use]
+    pub fn pending(&self) -> Option<(PressAction, Entity, Coordinates)> {
+        if self.consumed {
+            return None;
+        }
+        let (board, coords) = self.origin?;
+        Some((self.action()?, board, coords))
+    }
+
+    /// Updates the held state of a mouse button
+    pub fn set_held(&mut self, button: MouseButton, held: bool) {
+        match button {
+            MouseButton::Left => self.left = held,
+            MouseButton::Right => self.right = held,
+            MouseButton::Middle => self.middle = held,
+            MouseButton::Other(_) => (),
+        }
+    }
+
+    /// Are all the mouse buttons released
+    #[inline]
+    #[must_use]
+    pub fn is_released(&self) -> bool {
+        !(self.left || self.right || self.middle)
+    }
+}
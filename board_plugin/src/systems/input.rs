@@ -1,38 +1,154 @@
-use crate::events::{TileMarkEvent, TileTriggerEvent};
-use crate::Board;
+use crate::components::{AllowedMoves, Coordinates, ReadOnlyBoard};
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+use crate::{cursor_over_ui, Board, BoardAssets, PressAction, TilePress, TriggerMode};
 use bevy::input::{mouse::MouseButtonInput, ElementState};
 use bevy::log;
 use bevy::prelude::*;
 
+#[allow(clippy::too_many_arguments)]
 pub fn input_handling(
     windows: Res<Windows>,
-    board: Res<Board>,
+    boards: Query<(Entity, &Board, Option<&AllowedMoves>), Without<ReadOnlyBoard>>,
+    mut press: ResMut<TilePress>,
     mut button_evr: EventReader<MouseButtonInput>,
     mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
     mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
+    ui_nodes: Query<(&Node, &GlobalTransform)>,
 ) {
     let window = windows.get_primary().unwrap();
+    // We retrieve the hovered board and tile, the cursor never reaches the boards through UI nodes
+    let hovered = window
+        .cursor_position()
+        .filter(|pos| !cursor_over_ui(*pos, ui_nodes.iter()))
+        .and_then(|pos| {
+            boards.iter().find_map(|(entity, board, _)| {
+                board
+                    .mouse_position(window, pos)
+                    .map(|coords| (entity, board.options.trigger_mode, coords))
+            })
+        });
+    let tile_coordinates = hovered.map(|(entity, _, coords)| (entity, coords));
+    // Boards without allowed moves accept every move
+    let allowed = |board: Entity| boards.get(board).ok().and_then(|(_, _, a)| a);
+    // Dragging off the pressed tile cancels the press
+    if press.origin != tile_coordinates {
+        press.consumed = true;
+    }
 
     for event in button_evr.iter() {
-        if let ElementState::Pressed = event.state {
-            let position = window.cursor_position();
-            if let Some(pos) = position {
-                log::trace!("Mouse button pressed: {:?} at {}", event.button, pos);
-                let tile_coordinates = board.mouse_position(window, pos);
-                if let Some(coordinates) = tile_coordinates {
-                    match event.button {
-                        MouseButton::Left => {
-                            log::info!("Trying to uncover tile on {}", coordinates);
-                            tile_trigger_ewr.send(TileTriggerEvent(coordinates));
+        match event.state {
+            ElementState::Pressed => {
+                log::trace!("Mouse button pressed: {:?}", event.button);
+                press.set_held(event.button, true);
+                press.origin = tile_coordinates;
+                press.consumed = false;
+                // Marking a tile always happens on press
+                if event.button == MouseButton::Right && press.action().is_none() {
+                    if let Some((board, coords)) = tile_coordinates {
+                        if !allowed(board).is_none_or(|a| a.mark.contains(&coords)) {
+                            log::debug!("Marking tile {} is not allowed", coords);
+                            continue;
                         }
-                        MouseButton::Right => {
-                            log::info!("Trying to mark tile on {}", coordinates);
-                            tile_mark_ewr.send(TileMarkEvent(coordinates))
+                        log::info!("Trying to mark tile on {}", coords);
+                        tile_mark_ewr.send(TileMarkEvent { board, coords });
+                    }
+                } else if let Some((_, TriggerMode::Press, _)) = hovered {
+                    if let Some((action, board, coords)) = press.pending() {
+                        if allowed(board).is_none_or(|a| a.allows(action, &coords)) {
+                            send_action(
+                                action,
+                                board,
+                                coords,
+                                &mut tile_trigger_ewr,
+                                &mut tile_chord_ewr,
+                            );
                         }
-                        _ => (),
+                        press.consumed = true;
+                    }
+                }
+            }
+            ElementState::Released => {
+                log::trace!("Mouse button released: {:?}", event.button);
+                if let Some((_, TriggerMode::Release, _)) = hovered {
+                    if let Some((action, board, coords)) = press
+                        .pending()
+                        .filter(|(a, b, c)| allowed(*b).is_none_or(|m| m.allows(*a, c)))
+                    {
+                        send_action(
+                            action,
+                            board,
+                            coords,
+                            &mut tile_trigger_ewr,
+                            &mut tile_chord_ewr,
+                        );
                     }
                 }
+                // Releasing any button ends the press, even if another one is still held
+                press.consumed = true;
+                press.set_held(event.button, false);
+            }
+        }
+    }
+}
+
+fn send_action(
+    action: PressAction,
+    board: Entity,
+    coords: Coordinates,
+    tile_trigger_ewr: &mut EventWriter<TileTriggerEvent>,
+    tile_chord_ewr: &mut EventWriter<TileChordEvent>,
+) {
+    match action {
+        PressAction::Uncover => {
+            log::info!("Trying to uncover tile on {}", coords);
+            tile_trigger_ewr.send(TileTriggerEvent { board, coords });
+        }
+        PressAction::Chord => {
+            log::info!("Trying to chord tile on {}", coords);
+            tile_chord_ewr.send(TileChordEvent { board, coords });
+        }
+    }
+}
+
+/// Shows the tile covers affected by the pending press as pressed
+pub fn press_preview(
+    boards: Query<(&Board, Option<&AllowedMoves>)>,
+    board_assets: Res<BoardAssets>,
+    mut press: ResMut<TilePress>,
+    mut covers: Query<(&mut Sprite, &mut Handle<Image>)>,
+) {
+    let targets = match press.pending() {
+        Some((action, board, coords)) => match (action, boards.get(board)) {
+            // Moves which are not allowed show no preview
+            (_, Ok((_, Some(allowed)))) if !allowed.allows(action, &coords) => Vec::new(),
+            (PressAction::Uncover, Ok((board, _))) => board
+                .tile_to_uncover(&coords)
+                .copied()
+                .into_iter()
+                .collect(),
+            (PressAction::Chord, Ok((board, _))) if !board.covered_tiles.contains_key(&coords) => {
+                board.adjacent_unmarked_tiles(coords)
             }
+            _ => Vec::new(),
+        },
+        None => Vec::new(),
+    };
+    if targets == press.pressed {
+        return;
+    }
+    for entity in press.pressed.iter().filter(|e| !targets.contains(e)) {
+        // The cover may have been uncovered in the meantime
+        if let Ok((mut sprite, mut texture)) = covers.get_mut(*entity) {
+            sprite.color = board_assets.covered_tile_material.color;
+            *texture = board_assets.covered_tile_material.texture.clone();
+        }
+    }
+    for entity in targets.iter().filter(|e| !press.pressed.contains(e)) {
+        if let Ok((mut sprite, mut texture)) = covers.get_mut(*entity) {
+            sprite.color = board_assets.pressed_tile_material.color;
+            *texture = board_assets.pressed_tile_material.texture.clone();
         }
     }
+    press.pressed = targets;
 }
@@ -0,0 +1,115 @@
+use crate::components::{Coordinates, CounterMarker};
+use crate::Board;
+use bevy::log;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+
+/// Recomputes the tile size and position of every board when the primary window is resized, and
+/// updates the board entities in place
+#[allow(clippy::too_many_arguments)]
+pub fn relayout_board(
+    mut boards: Query<(Entity, &mut Board)>,
+    windows: Res<Windows>,
+    mut resize_evr: EventReader<WindowResized>,
+    tiles: Query<(&Coordinates, &Children)>,
+    children: Query<&Children>,
+    mut transforms: Query<&mut Transform>,
+    mut sprites: Query<&mut Sprite>,
+    mut texts: Query<&mut Text>,
+    markers: Query<(), With<CounterMarker>>,
+) {
+    let window = match windows.get_primary() {
+        Some(w) => w,
+        None => return,
+    };
+    if !resize_evr.iter().any(|e| e.id == window.id()) {
+        return;
+    }
+    for (board_entity, mut board) in boards.iter_mut() {
+        let tile_size = board.options.tile_size_for(
+            window,
+            (
+                board.game.tile_map().width(),
+                board.game.tile_map().height(),
+            ),
+        );
+        let board_size = Vec2::new(
+            board.game.tile_map().width() as f32 * tile_size,
+            board.game.tile_map().height() as f32 * tile_size,
+        );
+        let board_position = board.options.board_position(window, board_size);
+        log::info!("board resized: {} at {}", board_size, board_position);
+        let inner_size = Vec2::splat(tile_size - board.options.tile_padding);
+
+        if let Ok(mut transform) = transforms.get_mut(board_entity) {
+            transform.translation = board_position;
+        }
+        let board_children = match children.get(board_entity) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Failed to retrieve board children: {}", e);
+                continue;
+            }
+        };
+        for child in board_children.iter() {
+            let (coords, tile_children) = match tiles.get(*child) {
+                Ok(v) => v,
+                // The only board child without coordinates is the background
+                Err(_) => {
+                    if let Ok(mut sprite) = sprites.get_mut(*child) {
+                        sprite.custom_size = Some(board_size);
+                    }
+                    if let Ok(mut transform) = transforms.get_mut(*child) {
+                        transform.translation = (board_size / 2.).extend(transform.translation.z);
+                    }
+                    continue;
+                }
+            };
+            if let Ok(mut transform) = transforms.get_mut(*child) {
+                let position =
+                    Vec2::new(coords.x as f32, coords.y as f32) * tile_size + tile_size / 2.;
+                transform.translation = position.extend(transform.translation.z);
+            }
+            if let Ok(mut sprite) = sprites.get_mut(*child) {
+                sprite.custom_size = Some(inner_size);
+            }
+            // Tile cover, bomb and bomb counter
+            for tile_child in tile_children.iter() {
+                // Counter markers are laid out for a tile of size 1
+                if markers.get(*tile_child).is_ok() {
+                    if let Ok(mut transform) = transforms.get_mut(*tile_child) {
+                        transform.translation =
+                            (inner_size * Vec2::new(0.33, -0.33)).extend(transform.translation.z);
+                        transform.scale = inner_size.extend(1.);
+                    }
+                    continue;
+                }
+                if let Ok(mut sprite) = sprites.get_mut(*tile_child) {
+                    sprite.custom_size = Some(inner_size);
+                }
+                if let Ok(mut text) = texts.get_mut(*tile_child) {
+                    for section in text.sections.iter_mut() {
+                        section.style.font_size = inner_size.x;
+                    }
+                }
+                // Flags and question marks are children of the tile cover
+                if let Ok(flags) = children.get(*tile_child) {
+                    for flag in flags.iter() {
+                        if let Ok(mut sprite) = sprites.get_mut(*flag) {
+                            sprite.custom_size = Some(Vec2::splat(tile_size));
+                        }
+                        if let Ok(mut text) = texts.get_mut(*flag) {
+                            for section in text.sections.iter_mut() {
+                                section.style.font_size = tile_size;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        board.tile_size = tile_size;
+        board.bounds.position = board_position.xy();
+        board.bounds.size = board_size;
+    }
+}
@@ -1,44 +1,137 @@
-use crate::events::TileMarkEvent;
+use crate::components::{FlagsGame, ReadOnlyBoard, RemoteBoard};
+use crate::events::{TileFlaggedEvent, TileMarkEvent};
 use crate::{Board, BoardAssets};
 use bevy::log;
 use bevy::prelude::*;
 
+#[allow(clippy::type_complexity)]
 pub fn mark_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
-    board_assets: Res<BoardAssets>,
+    mut boards: Query<
+        (&mut Board, Option<&FlagsGame>),
+        (Without<RemoteBoard>, Without<ReadOnlyBoard>),
+    >,
+    board_assets: Option<Res<BoardAssets>>,
     mut tile_mark_event_rdr: EventReader<TileMarkEvent>,
+    mut tile_flagged_event_wr: EventWriter<TileFlaggedEvent>,
     query: Query<&Children>,
 ) {
     for event in tile_mark_event_rdr.iter() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
+        let mut board = match boards.get_mut(event.board) {
+            Ok((_, Some(_))) => {
+                log::debug!("Tiles can't be marked in flags mode");
+                continue;
+            }
+            Ok((b, None)) => b,
+            // Remote and read only boards are marked by the network layer
+            Err(_) => continue,
+        };
+        // A question mark is cleared by the next mark
+        if board.question_marks.remove(&event.coords) {
+            if let (Some(_), Some(entity)) = (&board_assets, board.covered_tiles.get(&event.coords))
+            {
+                remove_flag(&mut commands, *entity, &query);
+            }
+            continue;
+        }
+        let (entity, mark) = match board.try_toggle_mark(&event.coords) {
+            Some(v) => v,
+            None => continue,
+        };
+        tile_flagged_event_wr.send(TileFlaggedEvent {
+            board: event.board,
+            coords: event.coords,
+            flagged: mark,
+        });
+        let question_mark = !mark && board.options.question_marks;
+        if question_mark {
+            board.question_marks.insert(event.coords);
+        }
+        // Headless boards have no flag to display
+        if let Some(board_assets) = board_assets.as_deref() {
             if mark {
-                commands.entity(entity).with_children(|parent| {
-                    parent
-                        .spawn_bundle(SpriteBundle {
-                            texture: board_assets.flag_material.texture.clone(),
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::splat(board.tile_size)),
-                                color: board_assets.flag_material.color,
-                                ..Default::default()
-                            },
-                            transform: Transform::from_xyz(0., 0., 1.),
-                            ..Default::default()
-                        })
-                        .insert(Name::new("Flag"));
-                });
+                spawn_flag(
+                    &mut commands,
+                    entity,
+                    board.tile_size,
+                    board_assets,
+                    board_assets.flag_material.color,
+                );
             } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrieve flag components: {}", e);
-                        continue;
-                    }
-                };
-                for child in children.iter() {
-                    commands.entity(*child).despawn_recursive();
+                remove_flag(&mut commands, entity, &query);
+                if question_mark {
+                    spawn_question_mark(&mut commands, entity, board.tile_size, board_assets);
                 }
             }
         }
     }
 }
+
+/// Spawns a flag of the given `color` on a tile cover
+pub(crate) fn spawn_flag(
+    commands: &mut Commands,
+    cover: Entity,
+    size: f32,
+    board_assets: &BoardAssets,
+    color: Color,
+) {
+    commands.entity(cover).with_children(|parent| {
+        parent
+            .spawn_bundle(SpriteBundle {
+                texture: board_assets.flag_material.texture.clone(),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(size)),
+                    color,
+                    ..Default::default()
+                },
+                transform: Transform::from_xyz(0., 0., 1.),
+                ..Default::default()
+            })
+            .insert(Name::new("Flag"));
+    });
+}
+
+/// Spawns a question mark on a tile cover
+fn spawn_question_mark(
+    commands: &mut Commands,
+    cover: Entity,
+    size: f32,
+    board_assets: &BoardAssets,
+) {
+    commands.entity(cover).with_children(|parent| {
+        parent
+            .spawn_bundle(Text2dBundle {
+                text: Text {
+                    sections: vec![TextSection {
+                        value: "?".to_string(),
+                        style: TextStyle {
+                            color: board_assets.flag_material.color,
+                            font: board_assets.bomb_counter_font.clone(),
+                            font_size: size,
+                        },
+                    }],
+                    alignment: TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                },
+                transform: Transform::from_xyz(0., 0., 1.),
+                ..Default::default()
+            })
+            .insert(Name::new("Question Mark"));
+    });
+}
+
+/// Removes the flag or question mark of a tile cover
+pub(crate) fn remove_flag(commands: &mut Commands, cover: Entity, query: &Query<&Children>) {
+    let children = match query.get(cover) {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to retrieve flag components: {}", e);
+            return;
+        }
+    };
+    for child in children.iter() {
+        commands.entity(*child).despawn_recursive();
+    }
+}
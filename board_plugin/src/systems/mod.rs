@@ -1,3 +1,5 @@
 pub mod input;
+pub mod layout;
 pub mod mark;
+pub mod theme;
 pub mod uncover;
@@ -0,0 +1,143 @@
+use crate::components::{BombNeighbor, Coordinates, CounterMarker};
+use crate::{Board, BoardAssets};
+use bevy::log;
+use bevy::prelude::*;
+
+/// Pip positions in a 3x3 grid, from 1 to 8 pips
+const PIP_LAYOUTS: [&[(f32, f32)]; 8] = [
+    &[(0., 0.)],
+    &[(-1., 1.), (1., -1.)],
+    &[(-1., 1.), (0., 0.), (1., -1.)],
+    &[(-1., 1.), (1., 1.), (-1., -1.), (1., -1.)],
+    &[(-1., 1.), (1., 1.), (0., 0.), (-1., -1.), (1., -1.)],
+    &[
+        (-1., 1.),
+        (1., 1.),
+        (-1., 0.),
+        (1., 0.),
+        (-1., -1.),
+        (1., -1.),
+    ],
+    &[
+        (-1., 1.),
+        (1., 1.),
+        (-1., 0.),
+        (0., 0.),
+        (1., 0.),
+        (-1., -1.),
+        (1., -1.),
+    ],
+    &[
+        (-1., 1.),
+        (0., 1.),
+        (1., 1.),
+        (-1., 0.),
+        (1., 0.),
+        (-1., -1.),
+        (0., -1.),
+        (1., -1.),
+    ],
+];
+
+/// Spawns the pip pattern of a bomb counter in the bottom right corner of its tile.
+///
+/// The pattern is laid out for a tile of size 1 and scaled to `size`
+pub(crate) fn spawn_counter_marker(parent: &mut ChildBuilder, count: u8, color: Color, size: f32) {
+    let layout = PIP_LAYOUTS[(count.clamp(1, 8) - 1) as usize];
+    parent
+        .spawn()
+        .insert(Transform {
+            translation: Vec3::new(size * 0.33, -size * 0.33, 1.),
+            scale: Vec3::new(size, size, 1.),
+            ..Default::default()
+        })
+        .insert(GlobalTransform::default())
+        .insert(CounterMarker)
+        .insert(Name::new("Counter Marker"))
+        .with_children(|parent| {
+            for (x, y) in layout {
+                parent.spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(0.06)),
+                        color,
+                        ..Default::default()
+                    },
+                    transform: Transform::from_xyz(x * 0.09, y * 0.09, 0.),
+                    ..Default::default()
+                });
+            }
+        });
+}
+
+/// Applies the bomb counter colors and markers of the board assets to the existing tiles
+pub fn apply_board_assets(
+    mut commands: Commands,
+    board_assets: Option<Res<BoardAssets>>,
+    boards: Query<&Board>,
+    tiles: Query<(Entity, &BombNeighbor, &Parent, &Children)>,
+    markers: Query<(), With<CounterMarker>>,
+    mut texts: Query<&mut Text>,
+) {
+    let board_assets = match board_assets {
+        Some(a) if a.is_changed() => a,
+        _ => return,
+    };
+    for (entity, neighbor, parent, children) in tiles.iter() {
+        let color = board_assets.bomb_counter_color(neighbor.count);
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                for section in text.sections.iter_mut() {
+                    section.style.color = color;
+                }
+            }
+            if markers.get(*child).is_ok() {
+                // Markers are spawned again with the new colors
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+        if !board_assets.counter_markers {
+            continue;
+        }
+        let board = match boards.get(parent.0) {
+            Ok(b) => b,
+            Err(e) => {
+                log::error!("Failed to retrieve tile board: {}", e);
+                continue;
+            }
+        };
+        let size = board.tile_size - board.options.tile_padding;
+        commands
+            .entity(entity)
+            .with_children(|parent| spawn_counter_marker(parent, neighbor.count, color, size));
+    }
+}
+
+/// Applies the background, tile and cover colors of the board assets to the existing boards
+pub fn apply_board_materials(
+    board_assets: Option<Res<BoardAssets>>,
+    boards: Query<(&Board, &Children)>,
+    tiles: Query<(), With<Coordinates>>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let board_assets = match board_assets {
+        Some(a) if a.is_changed() => a,
+        _ => return,
+    };
+    for (board, children) in boards.iter() {
+        // Board children are its tiles and its background
+        for child in children.iter() {
+            if let Ok(mut sprite) = sprites.get_mut(*child) {
+                sprite.color = if tiles.get(*child).is_ok() {
+                    board_assets.tile_material.color
+                } else {
+                    board_assets.board_material.color
+                };
+            }
+        }
+        for cover in board.covered_tiles.values() {
+            if let Ok(mut sprite) = sprites.get_mut(*cover) {
+                sprite.color = board_assets.covered_tile_material.color;
+            }
+        }
+    }
+}
@@ -1,60 +1,192 @@
-use crate::components::{Bomb, BombNeighbor, Coordinates, Uncover};
-use crate::events::{BoardCompletedEvent, BombExplosionEvent, TileTriggerEvent};
-use crate::Board;
+use crate::components::{Bomb, Coordinates, FlagsGame, ReadOnlyBoard, RemoteBoard, Uncover};
+use crate::events::{
+    BoardCompletedEvent, BombExplosionEvent, CascadeFinishedEvent, TileChordEvent,
+    TileTriggerEvent, TileUncoveredEvent,
+};
+use crate::{Board, BoardAssets};
 use bevy::log;
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn uncover_tiles(
     mut commands: Commands,
-    mut board: ResMut<Board>,
-    children: Query<(Entity, &Parent), With<Uncover>>,
-    parents: Query<(&Coordinates, Option<&Bomb>, Option<&BombNeighbor>)>,
+    mut boards: Query<(&mut Board, Option<&mut FlagsGame>)>,
+    board_assets: Option<Res<BoardAssets>>,
+    children: Query<(Entity, &Parent, &Uncover)>,
+    parents: Query<(&Coordinates, &Parent, &Children, Option<&Bomb>)>,
+    mut sprites: Query<&mut Sprite>,
     mut board_completed_event_wr: EventWriter<BoardCompletedEvent>,
     mut bomb_explosion_event_wr: EventWriter<BombExplosionEvent>,
+    mut tile_uncovered_event_wr: EventWriter<TileUncoveredEvent>,
+    mut cascade_finished_event_wr: EventWriter<CascadeFinishedEvent>,
 ) {
-    // We iterate through tile covers to uncover
-    for (entity, parent) in children.iter() {
+    // Tiles uncovered on every board this frame
+    let mut revealed: HashMap<Entity, usize> = HashMap::default();
+    // We iterate through tile covers of the tiles uncovered by the game
+    for (entity, parent, uncover) in children.iter() {
         // we destroy the tile cover entity
         commands.entity(entity).despawn_recursive();
-        let (coords, bomb, bomb_counter) = match parents.get(parent.0) {
+        let (coords, board_entity, tile_children, bomb) = match parents.get(parent.0) {
             Ok(v) => v,
             Err(e) => {
                 log::error!("{}", e);
                 continue;
             }
         };
+        // The tile parent is the board
+        let (mut board, flags_game) = match boards.get_mut(board_entity.0) {
+            Ok(b) => b,
+            Err(e) => {
+                log::error!("Failed to retrieve tile board: {}", e);
+                continue;
+            }
+        };
         // We remove the entity from the board covered tile map
-        match board.try_uncover_tile(coords) {
+        match board.covered_tiles.remove(coords) {
             None => log::debug!("Tried to uncover an already uncovered tile"),
             Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coords, e),
         }
-        if board.is_completed() {
+        board.question_marks.remove(coords);
+        tile_uncovered_event_wr.send(TileUncoveredEvent {
+            board: board_entity.0,
+            coords: *coords,
+            tile: board.game.tile_map()[coords.y as usize][coords.x as usize],
+        });
+        *revealed.entry(board_entity.0).or_default() += 1;
+        if let Some(mut flags_game) = flags_game {
+            // In flags mode bombs are claimed instead of exploding
+            if bomb.is_some() {
+                // The turn may have changed since the claim was played
+                let player = uncover.claimed_by.unwrap_or(flags_game.current_player);
+                log::info!("Player {} claimed the bomb on {}", player + 1, coords);
+                if let Some(board_assets) = board_assets.as_deref() {
+                    for child in tile_children.iter().filter(|c| **c != entity) {
+                        if let Ok(mut sprite) = sprites.get_mut(*child) {
+                            sprite.color = board_assets.player_color(player.into());
+                        }
+                    }
+                }
+                flags_game.claim_bomb(player, board.game.tile_map().bomb_count());
+                if let Some(outcome) = flags_game.outcome {
+                    log::info!("Flags game over: {:?}", outcome);
+                    board_completed_event_wr.send(BoardCompletedEvent {
+                        board: board_entity.0,
+                    });
+                }
+                continue;
+            }
+        } else if board.game.is_completed() && board.is_displayed() {
             log::info!("Board completed");
-            board_completed_event_wr.send(BoardCompletedEvent);
+            board_completed_event_wr.send(BoardCompletedEvent {
+                board: board_entity.0,
+            });
         }
         if bomb.is_some() {
             log::info!("Boom !");
-            bomb_explosion_event_wr.send(BombExplosionEvent);
-        }
-        // If the tile is empty..
-        else if bomb_counter.is_none() {
-            // .. We propagate the uncovering by adding the `Uncover` component to adjacent tiles
-            // which will then be removed next frame
-            for entity in board.adjacent_covered_tiles(*coords) {
-                commands.entity(entity).insert(Uncover);
-            }
+            bomb_explosion_event_wr.send(BombExplosionEvent {
+                board: board_entity.0,
+                coords: *coords,
+            });
         }
     }
+    for (board, tiles_revealed) in revealed {
+        cascade_finished_event_wr.send(CascadeFinishedEvent {
+            board,
+            tiles_revealed,
+        });
+    }
 }
 
+/// Removes the covers of the tiles uncovered by a move, claimed by `claimed_by` in flags games
+fn uncover_covers(
+    commands: &mut Commands,
+    board: &Board,
+    revealed: &[Coordinates],
+    claimed_by: Option<u8>,
+) {
+    for entity in board.cover_entities(revealed) {
+        commands.entity(entity).insert(Uncover { claimed_by });
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub fn trigger_event_handler(
     mut commands: Commands,
-    board: Res<Board>,
+    mut boards: Query<
+        (&mut Board, Option<&mut FlagsGame>),
+        (Without<RemoteBoard>, Without<ReadOnlyBoard>),
+    >,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
 ) {
     for trigger_event in tile_trigger_evr.iter() {
-        if let Some(entity) = board.tile_to_uncover(&trigger_event.0) {
-            commands.entity(*entity).insert(Uncover);
+        let (mut board, flags_game) = match boards.get_mut(trigger_event.board) {
+            Ok(b) => b,
+            // Remote and read only boards are uncovered by the network layer
+            Err(_) => continue,
+        };
+        if flags_game.as_ref().is_some_and(|f| f.is_over()) {
+            continue;
+        }
+        let coords = trigger_event.coords;
+        // In flags mode the bombs are still claimed once every safe tile is uncovered
+        let claim = flags_game.is_some()
+            && board.game.tile_map().contains(coords)
+            && board.game.tile_map().is_bomb_at(coords)
+            && !board.game.is_flagged(coords);
+        let result = if claim {
+            board
+                .game
+                .claim(coords)
+                .map(|uncovered| if uncovered { vec![coords] } else { Vec::new() })
+        } else {
+            board.game.reveal(coords)
+        };
+        let revealed = match result {
+            Ok(r) if !r.is_empty() => r,
+            Ok(_) => continue,
+            Err(e) => {
+                log::debug!("Ignored trigger: {}", e);
+                continue;
+            }
+        };
+        let claimed_by = flags_game
+            .as_ref()
+            .filter(|_| claim)
+            .map(|f| f.current_player);
+        // Finding a bomb gives another turn
+        if let Some(mut flags_game) = flags_game {
+            if !claim {
+                flags_game.next_turn();
+            }
+        }
+        uncover_covers(&mut commands, &board, &revealed, claimed_by);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn chord_event_handler(
+    mut commands: Commands,
+    mut boards: Query<
+        &mut Board,
+        (
+            Without<FlagsGame>,
+            Without<RemoteBoard>,
+            Without<ReadOnlyBoard>,
+        ),
+    >,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+) {
+    for chord_event in tile_chord_evr.iter() {
+        // Chording is not available in flags mode, remote and read only boards are uncovered by the
+        // network layer
+        let mut board = match boards.get_mut(chord_event.board) {
+            Ok(b) => b,
+            Err(_) => continue,
+        };
+        match board.game.chord(chord_event.coords) {
+            Ok(revealed) => uncover_covers(&mut commands, &board, &revealed, None),
+            Err(e) => log::debug!("Ignored chord: {}", e),
         }
     }
 }
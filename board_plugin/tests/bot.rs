@@ -0,0 +1,56 @@
+use bevy::app::Events;
+use bevy::prelude::*;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::{Board, BoardOptions, BoardPlugin, BotPlugin};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum TestState {
+    Playing,
+}
+
+#[test]
+fn bot_finishes_the_board() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            map_size: (9, 9),
+            bomb_count: 10,
+            safe_start: true,
+            // A board the bot clears without exploding
+            seed: Some(7),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true))
+        .add_plugin(BotPlugin {
+            running_state: TestState::Playing,
+            interval: Duration::from_nanos(1),
+            enabled: true,
+        });
+    for _ in 0..1000 {
+        app.update();
+        let completed = !app
+            .world
+            .get_resource::<Events<BoardCompletedEvent>>()
+            .unwrap()
+            .is_empty();
+        let exploded = !app
+            .world
+            .get_resource::<Events<BombExplosionEvent>>()
+            .unwrap()
+            .is_empty();
+        if completed || exploded {
+            let (_, board) = app
+                .world
+                .query::<(Entity, &Board)>()
+                .iter(&app.world)
+                .next()
+                .unwrap();
+            assert!(board.game.is_completed());
+            assert!(board.game.explosions().is_empty());
+            return;
+        }
+    }
+    panic!("The bot didn't finish the board");
+}
@@ -0,0 +1,39 @@
+use board_plugin::components::{FlagsGame, FlagsOutcome};
+
+#[test]
+fn majority_wins_the_game() {
+    let mut game = FlagsGame::new(2);
+    game.claim_bomb(1, 5);
+    game.claim_bomb(1, 5);
+    assert!(!game.is_over());
+    game.claim_bomb(1, 5);
+    assert_eq!(game.scores, vec![0, 3]);
+    assert_eq!(game.outcome, Some(FlagsOutcome::Winner(1)));
+}
+
+#[test]
+fn shared_bombs_are_a_draw() {
+    let mut game = FlagsGame::new(2);
+    game.claim_bomb(0, 4);
+    game.claim_bomb(0, 4);
+    game.claim_bomb(1, 4);
+    assert!(!game.is_over());
+    game.claim_bomb(1, 4);
+    assert_eq!(game.scores, vec![2, 2]);
+    assert_eq!(game.outcome, Some(FlagsOutcome::Draw));
+}
+
+#[test]
+fn claims_keep_the_turn() {
+    let mut game = FlagsGame::new(3);
+    game.claim_bomb(0, 10);
+    assert_eq!(game.current_player, 0);
+    game.next_turn();
+    assert_eq!(game.current_player, 1);
+    game.claim_bomb(1, 10);
+    assert_eq!(game.current_player, 1);
+    assert_eq!(game.scores, vec![1, 1, 0]);
+    game.next_turn();
+    game.next_turn();
+    assert_eq!(game.current_player, 0);
+}
@@ -0,0 +1,372 @@
+use bevy::app::Events;
+use bevy::ecs::schedule::ShouldRun;
+use bevy::prelude::*;
+use board_plugin::components::{Coordinates, FlagsGame, FlagsOutcome, ReadOnlyBoard};
+use board_plugin::events::{
+    BoardCompletedEvent, BombExplosionEvent, CascadeFinishedEvent, TileFlaggedEvent, TileMarkEvent,
+    TileTriggerEvent, TileUncoveredEvent,
+};
+use board_plugin::minesweeper_core::{BoardCode, GameStatus, Tile, TileMap};
+use board_plugin::{cursor_over_ui, Board, BoardOptions, BoardPlugin, BoardSystem, GameMode};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum TestState {
+    Playing,
+}
+
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            map_size: (10, 10),
+            bomb_count: 10,
+            safe_start: true,
+            seed: Some(42),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    // Board creation, then safe start uncovering
+    app.update();
+    app.update();
+    app
+}
+
+fn first_board(app: &mut App) -> (Entity, &Board) {
+    app.world
+        .query::<(Entity, &Board)>()
+        .iter(&app.world)
+        .next()
+        .expect("No board was spawned")
+}
+
+#[test]
+fn headless_board_is_created() {
+    let mut app = headless_app();
+    let (_, board) = first_board(&mut app);
+    assert_eq!(board.options.seed, Some(42));
+    assert!(board.covered_tiles.len() < 100);
+}
+
+#[test]
+fn headless_board_handles_events() {
+    let mut app = headless_app();
+    let (entity, board) = first_board(&mut app);
+    let covered: Vec<Coordinates> = board.covered_tiles.keys().copied().collect();
+    let bomb = *covered
+        .iter()
+        .find(|c| board.game.tile_map().is_bomb_at(**c))
+        .unwrap();
+
+    send_mark(&mut app, entity, bomb);
+    app.update();
+    assert_eq!(board_marks(&mut app), vec![bomb]);
+    let flagged: Vec<_> = events::<TileFlaggedEvent>(&app)
+        .into_iter()
+        .map(|e| (e.coords, e.flagged))
+        .collect();
+    assert_eq!(flagged, vec![(bomb, true)]);
+
+    // Marked tiles are protected from triggers
+    send_trigger(&mut app, entity, bomb);
+    app.update();
+    assert!(explosions(&app).is_empty());
+
+    send_mark(&mut app, entity, bomb);
+    app.update();
+    assert!(board_marks(&mut app).is_empty());
+    send_trigger(&mut app, entity, bomb);
+    // The tile is uncovered the frame after the trigger
+    app.update();
+    app.update();
+    assert_eq!(explosions(&app), vec![(entity, bomb)]);
+    let uncovered = events::<TileUncoveredEvent>(&app);
+    assert_eq!(uncovered.len(), 1);
+    assert_eq!((uncovered[0].coords, uncovered[0].tile), (bomb, Tile::Bomb));
+    let cascades = events::<CascadeFinishedEvent>(&app);
+    assert_eq!(cascades.len(), 1);
+    assert_eq!(cascades[0].tiles_revealed, 1);
+    let (_, board) = first_board(&mut app);
+    assert!(!board.covered_tiles.contains_key(&bomb));
+}
+
+#[test]
+fn question_marks_follow_flags() {
+    let mut app = headless_app();
+    let (entity, board) = first_board(&mut app);
+    let tile = *board.covered_tiles.keys().next().unwrap();
+    app.world
+        .get_mut::<Board>(entity)
+        .unwrap()
+        .options
+        .question_marks = true;
+
+    // Flag, question mark, then cleared
+    send_mark(&mut app, entity, tile);
+    app.update();
+    send_mark(&mut app, entity, tile);
+    app.update();
+    assert!(board_marks(&mut app).is_empty());
+    assert!(first_board(&mut app).1.question_marks.contains(&tile));
+    send_mark(&mut app, entity, tile);
+    app.update();
+    assert!(board_marks(&mut app).is_empty());
+    assert!(first_board(&mut app).1.question_marks.is_empty());
+}
+
+#[test]
+fn hand_made_tile_maps_replace_generation() {
+    let mut app = App::new();
+    let bomb = Coordinates { x: 1, y: 0 };
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::with_bombs(5, 4, [bomb])),
+            safe_start: true,
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    app.update();
+    app.update();
+    let (_, board) = first_board(&mut app);
+    assert_eq!(board.options.map_size, (5, 4));
+    assert_eq!(board.options.bomb_count, 1);
+    assert!(board.game.tile_map().is_bomb_at(bomb));
+    assert_eq!(board.covered_tiles.len(), 2);
+}
+
+#[test]
+fn start_cells_are_uncovered() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::from_rows(&["*....", ".....", "...*."]).unwrap()),
+            start: Some(Coordinates { x: 4, y: 2 }),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    app.update();
+    // The start cascades through the empty tiles, the bottom left corner is walled by numbers
+    let (_, board) = first_board(&mut app);
+    let covered = |x, y| board.covered_tiles.contains_key(&Coordinates { x, y });
+    assert!(!covered(4, 2));
+    assert!(!covered(2, 2));
+    assert!(covered(0, 0));
+    assert!(covered(3, 0));
+}
+
+#[test]
+fn flags_games_are_played_to_the_end() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::from_rows(&["*.*", "...", "*.*"]).unwrap()),
+            safe_start: false,
+            game_mode: GameMode::Flags { players: 2 },
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    app.update();
+    let (entity, _) = first_board(&mut app);
+    let play = |app: &mut App, x, y| {
+        send_trigger(app, entity, Coordinates { x, y });
+        app.update();
+        app.update();
+    };
+    let current_player = |app: &App| app.world.get::<FlagsGame>(entity).unwrap().current_player;
+    // Every safe tile passes the turn, player 2 plays next
+    for (x, y) in [(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)] {
+        let player = current_player(&app);
+        play(&mut app, x, y);
+        assert_ne!(current_player(&app), player);
+    }
+    assert!(first_board(&mut app).1.game.is_completed());
+    // The bombs are still claimed without passing the turn, the third one is a majority
+    for (x, y) in [(0, 0), (2, 0), (0, 2)] {
+        play(&mut app, x, y);
+        assert_eq!(current_player(&app), 1);
+    }
+    let flags_game = app.world.get::<FlagsGame>(entity).unwrap();
+    assert_eq!(flags_game.scores, vec![0, 3]);
+    assert_eq!(flags_game.outcome, Some(FlagsOutcome::Winner(1)));
+    assert_eq!(events::<BoardCompletedEvent>(&app).len(), 1);
+    // Claimed bombs don't explode
+    assert!(explosions(&app).is_empty());
+    let game = &first_board(&mut app).1.game;
+    assert_eq!(game.claims().len(), 3);
+    assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn flags_claims_go_to_the_player_who_played_them() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::from_rows(&["*..", "...", "..*"]).unwrap()),
+            safe_start: false,
+            game_mode: GameMode::Flags { players: 2 },
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    app.update();
+    let (entity, _) = first_board(&mut app);
+    // The first player claims a bomb then passes the turn on a safe tile, in the same frame
+    send_trigger(&mut app, entity, Coordinates { x: 0, y: 2 });
+    send_trigger(&mut app, entity, Coordinates { x: 1, y: 1 });
+    app.update();
+    app.update();
+    let flags_game = app.world.get::<FlagsGame>(entity).unwrap();
+    assert_eq!(flags_game.scores, vec![1, 0]);
+    assert_eq!(flags_game.current_player, 1);
+}
+
+#[test]
+fn read_only_boards_ignore_local_moves() {
+    let mut app = headless_app();
+    let (entity, board) = first_board(&mut app);
+    let coords = board.game.covered().next().unwrap();
+    let covered = board.game.covered_count();
+    app.world.entity_mut(entity).insert(ReadOnlyBoard);
+    send_trigger(&mut app, entity, coords);
+    send_mark(&mut app, entity, coords);
+    app.update();
+    app.update();
+    let board = first_board(&mut app).1;
+    assert_eq!(board.game.covered_count(), covered);
+    assert!(board_marks(&mut app).is_empty());
+}
+
+#[test]
+fn board_codes_replay_the_same_board() {
+    let mut app = headless_app();
+    let code = first_board(&mut app).1.code().to_string();
+    assert_eq!(code, format!("MS1-10x10-10-{:x}", 42));
+
+    let tile_map = TileMap::with_bombs(5, 4, [Coordinates { x: 1, y: 0 }]);
+    let mut options = BoardOptions::default();
+    options.apply_code(&BoardCode::from_tile_map(&tile_map, false));
+    assert_eq!(options.map_size, (5, 4));
+    assert!(!options.safe_start);
+    assert_eq!(options.seed, None);
+    assert_eq!(**options.tile_map.as_ref().unwrap(), *tile_map);
+    options.apply_code(&code.parse().unwrap());
+    assert_eq!((options.map_size, options.seed), ((10, 10), Some(42)));
+    assert!(options.tile_map.is_none());
+}
+
+#[test]
+fn board_codes_keep_the_start_cell() {
+    let start = Coordinates { x: 3, y: 0 };
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::from_rows(&["*...", "....", "..*."]).unwrap()),
+            start: Some(start),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true));
+    app.update();
+    let code = first_board(&mut app).1.code();
+    assert_eq!(code.start, Some(start));
+    let mut options = BoardOptions::default();
+    options.apply_code(&code.to_string().parse().unwrap());
+    assert_eq!(options.start, Some(start));
+    assert!(!code.game().is_covered(start));
+}
+
+fn board_marks(app: &mut App) -> Vec<Coordinates> {
+    first_board(app).1.game.flags().collect()
+}
+
+fn send_mark(app: &mut App, board: Entity, coords: Coordinates) {
+    app.world
+        .get_resource_mut::<Events<TileMarkEvent>>()
+        .unwrap()
+        .send(TileMarkEvent { board, coords });
+}
+
+fn send_trigger(app: &mut App, board: Entity, coords: Coordinates) {
+    app.world
+        .get_resource_mut::<Events<TileTriggerEvent>>()
+        .unwrap()
+        .send(TileTriggerEvent { board, coords });
+}
+
+fn explosions(app: &App) -> Vec<(Entity, Coordinates)> {
+    events::<BombExplosionEvent>(app)
+        .into_iter()
+        .map(|e| (e.board, e.coords))
+        .collect()
+}
+
+fn events<T: Copy + Send + Sync + 'static>(app: &App) -> Vec<T> {
+    let events = app.world.get_resource::<Events<T>>().unwrap();
+    events.get_reader().iter(events).copied().collect()
+}
+
+struct Running(bool);
+
+fn running(running: Res<Running>) -> ShouldRun {
+    if running.0 {
+        ShouldRun::Yes
+    } else {
+        ShouldRun::No
+    }
+}
+
+#[test]
+fn run_criteria_lifecycle_creates_missing_boards() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(Running(false))
+        .insert_resource(BoardOptions {
+            seed: Some(42),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::with_run_criteria(running).headless(true))
+        .add_system(
+            BoardPlugin::<()>::cleanup_board
+                .after(BoardSystem::Uncover)
+                .with_run_criteria(|running: Res<Running>| {
+                    if running.0 {
+                        ShouldRun::No
+                    } else {
+                        ShouldRun::Yes
+                    }
+                }),
+        );
+    app.update();
+    assert_eq!(app.world.query::<&Board>().iter(&app.world).count(), 0);
+
+    app.world.insert_resource(Running(true));
+    app.update();
+    app.update();
+    assert_eq!(app.world.query::<&Board>().iter(&app.world).count(), 1);
+
+    // The app cleans the boards up, they are created again once running
+    app.world.insert_resource(Running(false));
+    app.update();
+    assert_eq!(app.world.query::<&Board>().iter(&app.world).count(), 0);
+    app.world.insert_resource(Running(true));
+    app.update();
+    app.update();
+    assert_eq!(app.world.query::<&Board>().iter(&app.world).count(), 1);
+}
+
+#[test]
+fn any_ui_node_covers_the_cursor() {
+    // A 100x40 text panel centered on (60, 30), without `Interaction`
+    let node = Node {
+        size: Vec2::new(100., 40.),
+    };
+    let transform = GlobalTransform::from_xyz(60., 30., 0.);
+    let nodes = [(&node, &transform)];
+    assert!(cursor_over_ui(Vec2::new(15., 15.), nodes));
+    assert!(cursor_over_ui(Vec2::new(105., 45.), nodes));
+    assert!(!cursor_over_ui(Vec2::new(5., 30.), nodes));
+    assert!(!cursor_over_ui(Vec2::new(60., 55.), nodes));
+}
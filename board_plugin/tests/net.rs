@@ -0,0 +1,410 @@
+use bevy::app::Events;
+use bevy::prelude::*;
+use board_plugin::components::Coordinates;
+use board_plugin::events::{TileMarkEvent, TileTriggerEvent};
+use board_plugin::minesweeper_core::{Game, MoveError, Tile, TileMap};
+use board_plugin::net::{
+    ClientMessage, Connection, PlayerProgress, RaceConfig, RaceServer, ServerBoard, ServerMessage,
+    SharedMoveError, SpectatorPublisherPlugin, SyncMessage, MAX_MESSAGE_LENGTH,
+    MAX_SYNC_MESSAGE_LENGTH,
+};
+use board_plugin::{Board, BoardOptions, BoardPlugin};
+use std::collections::{HashSet, VecDeque};
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Retries `f` until it returns a value, failing after a second
+fn wait_for<T>(mut f: impl FnMut() -> Option<T>) -> T {
+    let start = Instant::now();
+    loop {
+        if let Some(value) = f() {
+            return value;
+        }
+        assert!(start.elapsed() < Duration::from_secs(1), "timed out");
+        sleep(Duration::from_millis(5));
+    }
+}
+
+#[test]
+fn overlong_messages_close_the_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let mut server = Connection::new(listener.accept().unwrap().0).unwrap();
+
+    client.write_all(b"{\"Mark\":{\"x\":1,\"y\":2}}\n").unwrap();
+    let messages = wait_for(|| {
+        let messages = server.poll::<ClientMessage>().unwrap();
+        (!messages.is_empty()).then_some(messages)
+    });
+    assert!(matches!(messages[..], [Ok(ClientMessage::Mark(_))]));
+
+    // A line without end is refused once it outgrows the limit
+    client
+        .write_all(&vec![b'x'; MAX_MESSAGE_LENGTH + 1])
+        .unwrap();
+    wait_for(|| server.poll::<ClientMessage>().err());
+    assert!(server.is_closed());
+}
+
+/// Race client connection, with the messages received but not checked yet
+struct RaceClient {
+    connection: Connection,
+    inbox: VecDeque<ServerMessage>,
+}
+
+impl RaceClient {
+    fn join(server: &RaceServer, name: &str) -> Self {
+        let address = server.local_addr().unwrap().to_string();
+        let mut connection = Connection::connect(&address).unwrap();
+        let name = name.to_string();
+        connection.send(&ClientMessage::Join { name }).unwrap();
+        Self {
+            connection,
+            inbox: VecDeque::new(),
+        }
+    }
+
+    /// Runs the server until the client receives its next message
+    fn next(&mut self, server: &mut RaceServer) -> ServerMessage {
+        wait_for(|| {
+            server.step().unwrap();
+            let messages = self.connection.poll::<ServerMessage>().unwrap();
+            self.inbox.extend(messages.into_iter().map(Result::unwrap));
+            self.inbox.pop_front()
+        })
+    }
+
+    /// Runs the server until the client receives a progress matching `f`
+    fn progress(
+        &mut self,
+        server: &mut RaceServer,
+        f: impl Fn(&[PlayerProgress]) -> bool,
+    ) -> Vec<PlayerProgress> {
+        loop {
+            if let ServerMessage::Progress(progress) = self.next(server) {
+                if f(&progress) {
+                    return progress;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn race_server_plays_a_race_over_localhost() {
+    let options = BoardOptions {
+        map_size: (6, 6),
+        bomb_count: 4,
+        safe_start: false,
+        seed: Some(7),
+        ..Default::default()
+    };
+    let config = RaceConfig {
+        players: 2,
+        options: options.clone(),
+    };
+    let mut server = RaceServer::bind("127.0.0.1:0", config).unwrap();
+    let mut alice = RaceClient::join(&server, "Alice");
+    assert!(matches!(
+        alice.next(&mut server),
+        ServerMessage::Welcome { player: 0 }
+    ));
+    assert!(!server.is_started());
+    let mut bob = RaceClient::join(&server, "Bob");
+    assert!(matches!(
+        bob.next(&mut server),
+        ServerMessage::Welcome { player: 1 }
+    ));
+
+    // Both players get the same seeded board
+    for client in [&mut alice, &mut bob] {
+        match client.next(&mut server) {
+            ServerMessage::Start { options: o } => {
+                assert_eq!(o.game_options(), options.game_options());
+            }
+            m => panic!("Expected the race start, got {:?}", m),
+        }
+    }
+    assert!(server.is_started());
+    let progress = alice.progress(&mut server, |_| true);
+    let names: Vec<_> = progress.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["Alice", "Bob"]);
+    assert!(progress.iter().all(|p| p.cleared == 0.));
+
+    // Alice uncovers every safe tile, mirroring the cascades on her own board
+    let mut game = Game::new(&options.game_options());
+    let safe: Vec<Coordinates> = game
+        .tile_map()
+        .coordinates()
+        .filter(|c| !game.tile_map().is_bomb_at(*c))
+        .collect();
+    for coords in safe {
+        if game.is_covered(coords) {
+            game.reveal(coords).unwrap();
+            alice
+                .connection
+                .send(&ClientMessage::Uncover(coords))
+                .unwrap();
+        }
+    }
+    let finished = |progress: &[PlayerProgress]| progress[0].finish_time.is_some();
+    alice.progress(&mut server, finished);
+    let progress = bob.progress(&mut server, finished);
+    assert_eq!(progress[0].cleared, 100.);
+    assert_eq!(progress[0].explosions, 0);
+    assert_eq!((progress[1].cleared, progress[1].finish_time), (0., None));
+    let winner = progress.iter().find(|p| p.finish_time.is_some()).unwrap();
+    assert_eq!(winner.name, "Alice");
+}
+
+#[test]
+fn player_ids_stay_unique_past_256_players() {
+    let config = RaceConfig {
+        players: 300,
+        options: BoardOptions::default(),
+    };
+    let mut server = RaceServer::bind("127.0.0.1:0", config).unwrap();
+    let mut ids = HashSet::new();
+    let mut clients = Vec::new();
+    for i in 0..257 {
+        let mut client = RaceClient::join(&server, &format!("Player {}", i));
+        match client.next(&mut server) {
+            ServerMessage::Welcome { player } => assert!(ids.insert(player)),
+            m => panic!("Expected a welcome, got {:?}", m),
+        }
+        // Every player stays connected
+        clients.push(client);
+    }
+    assert_eq!(ids.len(), 257);
+}
+
+fn shared_board() -> ServerBoard {
+    ServerBoard::new(&BoardOptions {
+        map_size: (8, 8),
+        bomb_count: 10,
+        safe_start: false,
+        seed: Some(3),
+        ..Default::default()
+    })
+}
+
+/// A tile of the board showing a bomb count
+fn bomb_neighbor(board: &ServerBoard) -> Coordinates {
+    let tile_map = board.game().tile_map();
+    tile_map
+        .coordinates()
+        .find(|c| matches!(tile_map[c.y as usize][c.x as usize], Tile::BombNeighbor(_)))
+        .unwrap()
+}
+
+#[test]
+fn only_flag_owners_remove_their_flags() {
+    let mut board = shared_board();
+    let coords = bomb_neighbor(&board);
+    assert_eq!(board.toggle_mark(coords, 0), Ok(Some(0)));
+    assert_eq!(
+        board.toggle_mark(coords, 1),
+        Err(SharedMoveError::NotOwner { coords, owner: 0 })
+    );
+    assert_eq!(board.marks().collect::<Vec<_>>(), vec![(coords, 0)]);
+    // Flagged tiles are protected from every player
+    assert_eq!(board.uncover(coords), Ok(Vec::new()));
+    assert!(board.game().is_covered(coords));
+    assert_eq!(board.toggle_mark(coords, 0), Ok(None));
+    assert_eq!(board.marks().count(), 0);
+    // Once removed, anyone can flag the tile
+    assert_eq!(board.toggle_mark(coords, 1), Ok(Some(1)));
+}
+
+#[test]
+fn concurrent_uncovers_apply_once() {
+    let mut board = shared_board();
+    let coords = bomb_neighbor(&board);
+    // Two players uncover the same tile, the server applies the first message only
+    assert_eq!(board.uncover(coords), Ok(vec![coords]));
+    assert_eq!(board.uncover(coords), Ok(Vec::new()));
+    assert_eq!(
+        board.toggle_mark(coords, 1),
+        Err(SharedMoveError::Move(MoveError::Uncovered(coords)))
+    );
+    assert_eq!(board.game().uncovered().count(), 1);
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum TestState {
+    Playing,
+}
+
+fn published_board(app: &mut App) -> (Entity, &Board) {
+    app.world
+        .query::<(Entity, &Board)>()
+        .iter(&app.world)
+        .next()
+        .unwrap()
+}
+
+fn covered_safe_tile(board: &Board) -> Coordinates {
+    board
+        .game
+        .covered()
+        .find(|c| !board.game.tile_map().is_bomb_at(*c))
+        .unwrap()
+}
+
+/// Runs the app until the spectator receives messages
+fn spectate(app: &mut App, spectator: &mut Connection) -> Vec<SyncMessage> {
+    wait_for(|| {
+        app.update();
+        let messages = spectator.poll::<SyncMessage>().unwrap();
+        (!messages.is_empty()).then_some(messages)
+    })
+    .into_iter()
+    .map(Result::unwrap)
+    .collect()
+}
+
+fn connect_spectator(address: &str) -> Connection {
+    Connection::connect(address)
+        .unwrap()
+        .with_max_message_length(MAX_SYNC_MESSAGE_LENGTH)
+}
+
+/// Plays a move and returns the spectator updates
+fn play<E: Send + Sync + 'static>(
+    app: &mut App,
+    spectator: &mut Connection,
+    event: E,
+) -> Vec<SyncMessage> {
+    app.world
+        .get_resource_mut::<Events<E>>()
+        .unwrap()
+        .send(event);
+    spectate(app, spectator)
+}
+
+#[test]
+fn late_spectators_get_a_snapshot_then_deltas() {
+    // The publisher binds its own listener, on a port known to be free
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            map_size: (10, 10),
+            bomb_count: 10,
+            safe_start: true,
+            seed: Some(42),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true))
+        .add_plugin(SpectatorPublisherPlugin {
+            address: address.clone(),
+        });
+    app.update();
+    app.update();
+    let mut early = connect_spectator(&address);
+    assert!(matches!(
+        spectate(&mut app, &mut early)[..],
+        [SyncMessage::Snapshot { .. }]
+    ));
+
+    let (entity, board) = published_board(&mut app);
+    let first = covered_safe_tile(board);
+    let trigger = TileTriggerEvent {
+        board: entity,
+        coords: first,
+    };
+    let updates = play(&mut app, &mut early, trigger);
+    assert!(matches!(updates[..], [SyncMessage::Uncovered(_)]));
+
+    // The late spectator starts from the current board
+    let mut late = connect_spectator(&address);
+    let (uncovered, mut marks) = match spectate(&mut app, &mut late).remove(0) {
+        SyncMessage::Snapshot {
+            options,
+            uncovered,
+            marks,
+            ..
+        } => {
+            assert_eq!(options.seed, Some(42));
+            (uncovered, marks)
+        }
+        m => panic!("Expected a snapshot, got {:?}", m),
+    };
+    let mut uncovered: HashSet<Coordinates> = uncovered.into_iter().collect();
+    let board = published_board(&mut app).1;
+    assert_eq!(uncovered, board.game.uncovered().collect());
+    assert!(uncovered.contains(&first));
+    assert!(marks.is_empty());
+    let second = covered_safe_tile(board);
+
+    // Then only the moves
+    let mark = TileMarkEvent {
+        board: entity,
+        coords: second,
+    };
+    let trigger = TileTriggerEvent {
+        board: entity,
+        coords: second,
+    };
+    let mut updates = play(&mut app, &mut late, mark);
+    updates.extend(play(&mut app, &mut late, mark));
+    updates.extend(play(&mut app, &mut late, trigger));
+    for update in updates {
+        match update {
+            SyncMessage::Uncovered(tiles) => uncovered.extend(tiles),
+            SyncMessage::Marked {
+                coords,
+                owner: Some(owner),
+            } => marks.push((coords, owner)),
+            SyncMessage::Marked {
+                coords,
+                owner: None,
+            } => marks.retain(|(c, _)| *c != coords),
+            m => panic!("Expected a delta, got {:?}", m),
+        }
+    }
+    let game = &published_board(&mut app).1.game;
+    assert!(uncovered.contains(&second));
+    assert_eq!(uncovered, game.uncovered().collect());
+    assert!(marks.is_empty());
+}
+
+#[test]
+fn spectators_get_hand_made_boards() {
+    let address = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .to_string();
+    let rows = ["*...", "..*.", "...."];
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state(TestState::Playing)
+        .insert_resource(BoardOptions {
+            tile_map: Some(TileMap::from_rows(&rows).unwrap()),
+            start: Some(Coordinates { x: 0, y: 0 }),
+            ..Default::default()
+        })
+        .add_plugin(BoardPlugin::new(TestState::Playing).headless(true))
+        .add_plugin(SpectatorPublisherPlugin {
+            address: address.clone(),
+        });
+    app.update();
+    app.update();
+    let mut spectator = connect_spectator(&address);
+    match spectate(&mut app, &mut spectator).remove(0) {
+        SyncMessage::Snapshot { options, .. } => {
+            assert_eq!(options.seed, None);
+            assert_eq!(options.tile_map.unwrap().rows(), rows);
+            assert_eq!(options.start, Some(Coordinates { x: 0, y: 0 }));
+        }
+        m => panic!("Expected a snapshot, got {:?}", m),
+    }
+}
@@ -0,0 +1,311 @@
+use crate::{Coordinates, Game, GameOptions, TileMap};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Bomb placement of a shared board
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BoardLayout {
+    /// Generated board, with its seed
+    Seed(u64),
+    /// Hand-made board, with every bomb
+    Bombs(Vec<Coordinates>),
+}
+
+/// Text encoding of a board, to play the exact same board elsewhere.
+///
+/// Codes look like `MS1-20x20-50-3fa9c2`: the format version, the map size, the bomb count and the
+/// hexadecimal seed. The bomb count is followed by `n` when the board has no safe start, and
+/// hand-made boards replace the seed with `L` and their bomb layout, one bit per tile in row-major
+/// order from the bottom left, as hexadecimal. A start cell uncovered when the board begins
+/// follows as `@x,y`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BoardCode {
+    pub map_size: (u16, u16),
+    pub bomb_count: u16,
+    pub safe_start: bool,
+    pub layout: BoardLayout,
+    /// Tile uncovered when the board starts, like the start of hand-made puzzles
+    pub start: Option<Coordinates>,
+}
+
+/// Reason for rejecting a board code
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum BoardCodeError {
+    /// The code doesn't start with `MS` and a version
+    NotABoardCode,
+    /// The code was made by a newer version of the game
+    UnsupportedVersion(u32),
+    /// The code doesn't have 4 parts separated by `-`
+    MissingParts(usize),
+    /// The size isn't `<width>x<height>` with non zero dimensions
+    InvalidSize(String),
+    /// A side of the board is longer than `BoardCode::MAX_SIDE` tiles
+    TooLarge { width: u16, height: u16 },
+    /// The bomb count isn't a number
+    InvalidBombCount(String),
+    /// The bombs don't leave a single safe tile
+    TooManyBombs { bombs: u16, tiles: u32 },
+    /// The seed isn't hexadecimal
+    InvalidSeed(String),
+    /// The layout isn't hexadecimal, or doesn't match the map size
+    InvalidLayout,
+    /// The layout doesn't hold the announced number of bombs
+    BombCountMismatch { expected: u16, found: u32 },
+    /// The start cell isn't `x,y` on a safe tile of the board
+    InvalidStart(String),
+}
+
+impl Display for BoardCodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotABoardCode => write!(f, "not a board code, codes start with MS1-"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "board code version {} is not supported, update the game",
+                v
+            ),
+            Self::MissingParts(n) => write!(f, "expected 4 parts separated by '-', found {}", n),
+            Self::InvalidSize(s) => write!(f, "invalid board size '{}', expected WxH", s),
+            Self::TooLarge { width, height } => write!(
+                f,
+                "a {}x{} board is too large, sides are at most {} tiles",
+                width,
+                height,
+                BoardCode::MAX_SIDE
+            ),
+            Self::InvalidBombCount(s) => write!(f, "invalid bomb count '{}'", s),
+            Self::TooManyBombs { bombs, tiles } => {
+                write!(f, "{} bombs don't fit on {} tiles", bombs, tiles)
+            }
+            Self::InvalidSeed(s) => write!(f, "invalid seed '{}', expected hexadecimal", s),
+            Self::InvalidLayout => write!(f, "invalid bomb layout"),
+            Self::BombCountMismatch { expected, found } => write!(
+                f,
+                "the layout holds {} bombs instead of {}",
+                found, expected
+            ),
+            Self::InvalidStart(s) => write!(f, "invalid start cell '{}', expected x,y", s),
+        }
+    }
+}
+
+impl std::error::Error for BoardCodeError {}
+
+impl BoardCode {
+    /// Current format version
+    pub const VERSION: u32 = 1;
+    /// Longest board side accepted from a code, larger boards can't be spawned
+    pub const MAX_SIDE: u16 = 256;
+
+    /// Code of a generated board, `None` if its seed is not set
+    #[must_use]
+    pub fn seeded(options: &GameOptions) -> Option<Self> {
+        Some(Self {
+            map_size: options.map_size,
+            bomb_count: options.bomb_count,
+            safe_start: options.safe_start,
+            layout: BoardLayout::Seed(options.seed?),
+            start: None,
+        })
+    }
+
+    /// Code of a board holding every bomb of `tile_map`
+    #[must_use]
+    pub fn from_tile_map(tile_map: &TileMap, safe_start: bool) -> Self {
+        Self {
+            map_size: (tile_map.width(), tile_map.height()),
+            bomb_count: tile_map.bomb_count(),
+            safe_start,
+            layout: BoardLayout::Bombs(
+                tile_map
+                    .coordinates()
+                    .filter(|c| tile_map.is_bomb_at(*c))
+                    .collect(),
+            ),
+            start: None,
+        }
+    }
+
+    /// Generates the tile map of the board
+    #[must_use]
+    pub fn tile_map(&self) -> TileMap {
+        match &self.layout {
+            BoardLayout::Seed(seed) => TileMap::generate(&GameOptions {
+                map_size: self.map_size,
+                bomb_count: self.bomb_count,
+                safe_start: self.safe_start,
+                seed: Some(*seed),
+            }),
+            BoardLayout::Bombs(bombs) => {
+                TileMap::with_bombs(self.map_size.0, self.map_size.1, bombs.iter().copied())
+            }
+        }
+    }
+
+    /// Starts a game on the board, with its start cell uncovered
+    #[must_use]
+    pub fn game(&self) -> Game {
+        let mut game = Game::from_tile_map(self.tile_map(), self.safe_start);
+        if let Some(start) = self.start {
+            // Parsed codes only hold start cells on the board
+            let _ = game.reveal(start);
+        }
+        game
+    }
+
+    fn tile_count(&self) -> u32 {
+        u32::from(self.map_size.0) * u32::from(self.map_size.1)
+    }
+}
+
+impl Display for BoardCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (width, height) = self.map_size;
+        let safe_start = if self.safe_start { "" } else { "n" };
+        write!(
+            f,
+            "MS{}-{}x{}-{}{}-",
+            Self::VERSION,
+            width,
+            height,
+            self.bomb_count,
+            safe_start
+        )?;
+        match &self.layout {
+            BoardLayout::Seed(seed) => write!(f, "{:x}", seed)?,
+            BoardLayout::Bombs(bombs) => {
+                // 4 tiles per hexadecimal digit, the first tile on the highest bit
+                let mut nibbles = vec![0u8; (self.tile_count() as usize).div_ceil(4)];
+                for coords in bombs {
+                    let index = coords.y as usize * width as usize + coords.x as usize;
+                    nibbles[index / 4] |= 8 >> (index % 4);
+                }
+                f.write_str("L")?;
+                for nibble in nibbles {
+                    write!(f, "{:x}", nibble)?;
+                }
+            }
+        }
+        match self.start {
+            Some(start) => write!(f, "@{},{}", start.x, start.y),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for BoardCode {
+    type Err = BoardCodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split('-').collect();
+        let version = parts[0]
+            .get(..2)
+            .filter(|p| p.eq_ignore_ascii_case("MS"))
+            .and_then(|_| parts[0][2..].parse::<u32>().ok())
+            .ok_or(BoardCodeError::NotABoardCode)?;
+        if version != Self::VERSION {
+            return Err(BoardCodeError::UnsupportedVersion(version));
+        }
+        let (size, bombs, layout) = match parts[1..] {
+            [size, bombs, layout] => (size, bombs, layout),
+            _ => return Err(BoardCodeError::MissingParts(parts.len())),
+        };
+        let map_size = size
+            .split_once(['x', 'X'])
+            .and_then(|(w, h)| Some((w.parse::<u16>().ok()?, h.parse::<u16>().ok()?)))
+            .filter(|(w, h)| *w > 0 && *h > 0)
+            .ok_or_else(|| BoardCodeError::InvalidSize(size.to_string()))?;
+        if map_size.0 > Self::MAX_SIDE || map_size.1 > Self::MAX_SIDE {
+            return Err(BoardCodeError::TooLarge {
+                width: map_size.0,
+                height: map_size.1,
+            });
+        }
+        let (bomb_count, safe_start) = match bombs.strip_suffix('n') {
+            Some(count) => (count, false),
+            None => (bombs, true),
+        };
+        let bomb_count = bomb_count
+            .parse::<u16>()
+            .map_err(|_| BoardCodeError::InvalidBombCount(bombs.to_string()))?;
+        let mut code = Self {
+            map_size,
+            bomb_count,
+            safe_start,
+            layout: BoardLayout::Bombs(Vec::new()),
+            start: None,
+        };
+        let tiles = code.tile_count();
+        if u32::from(bomb_count) >= tiles {
+            return Err(BoardCodeError::TooManyBombs {
+                bombs: bomb_count,
+                tiles,
+            });
+        }
+        let (layout, start) = match layout.split_once('@') {
+            Some((layout, start)) => (layout, Some(start)),
+            None => (layout, None),
+        };
+        code.layout = match layout.strip_prefix(['L', 'l']) {
+            Some(nibbles) => BoardLayout::Bombs(decode_layout(nibbles, map_size, bomb_count)?),
+            None => BoardLayout::Seed(
+                u64::from_str_radix(layout, 16)
+                    .map_err(|_| BoardCodeError::InvalidSeed(layout.to_string()))?,
+            ),
+        };
+        if let Some(start) = start {
+            let invalid = || BoardCodeError::InvalidStart(start.to_string());
+            let (x, y) = start.split_once(',').ok_or_else(invalid)?;
+            let coords = Coordinates {
+                x: x.parse().map_err(|_| invalid())?,
+                y: y.parse().map_err(|_| invalid())?,
+            };
+            let on_board = coords.x < map_size.0 && coords.y < map_size.1;
+            // Bombs of seeded boards are only known once generated
+            let on_bomb =
+                matches!(&code.layout, BoardLayout::Bombs(bombs) if bombs.contains(&coords));
+            if !on_board || on_bomb {
+                return Err(invalid());
+            }
+            code.start = Some(coords);
+        }
+        Ok(code)
+    }
+}
+
+/// Decodes the bombs of a hexadecimal layout
+fn decode_layout(
+    nibbles: &str,
+    (width, height): (u16, u16),
+    bomb_count: u16,
+) -> Result<Vec<Coordinates>, BoardCodeError> {
+    let tiles = width as usize * height as usize;
+    if nibbles.len() != tiles.div_ceil(4) {
+        return Err(BoardCodeError::InvalidLayout);
+    }
+    let mut bombs = Vec::new();
+    for (i, c) in nibbles.chars().enumerate() {
+        let nibble = c.to_digit(16).ok_or(BoardCodeError::InvalidLayout)?;
+        for bit in 0..4 {
+            if nibble & (8 >> bit) == 0 {
+                continue;
+            }
+            let index = i * 4 + bit;
+            // Padding bits after the last tile must be empty
+            if index >= tiles {
+                return Err(BoardCodeError::InvalidLayout);
+            }
+            bombs.push(Coordinates {
+                x: (index % width as usize) as u16,
+                y: (index / width as usize) as u16,
+            });
+        }
+    }
+    if bombs.len() != bomb_count as usize {
+        return Err(BoardCodeError::BombCountMismatch {
+            expected: bomb_count,
+            found: bombs.len() as u32,
+        });
+    }
+    Ok(bombs)
+}
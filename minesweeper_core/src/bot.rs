@@ -0,0 +1,207 @@
+use crate::{Coordinates, Game, GameStatus, Tile};
+use std::fmt::{self, Display, Formatter};
+
+/// Move kind chosen by the bot
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum BotAction {
+    /// Uncover the tile
+    Reveal,
+    /// Flag the tile as a bomb
+    Flag,
+}
+
+/// Why the bot chose a move
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BotReason {
+    /// Every bomb around the `counter` tile is already flagged
+    Satisfied { counter: Coordinates },
+    /// The `counter` tile has as many bombs left as unflagged covered neighbors
+    Saturated { counter: Coordinates },
+    /// The unknown neighbors of `subset` are all neighbors of `counter`, and the bombs left
+    /// around both counters only fit one way
+    Subset {
+        counter: Coordinates,
+        subset: Coordinates,
+    },
+    /// No deduction is possible, the tile has the lowest estimated bomb `probability`
+    Guess { probability: f32 },
+}
+
+/// Move chosen by the bot, with its reason
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BotMove {
+    pub action: BotAction,
+    pub coords: Coordinates,
+    pub reason: BotReason,
+}
+
+/// Unflagged covered neighbors of an uncovered bomb neighbor, and the bombs left among them
+struct Constraint {
+    counter: Coordinates,
+    unknown: Vec<Coordinates>,
+    bombs: usize,
+}
+
+impl BotMove {
+    /// Chooses the next move of `game`, trusting its flags.
+    ///
+    /// Logical deductions come first, then the covered tile with the lowest bomb probability is
+    /// uncovered. Returns `None` once the game is over
+    #[must_use]
+    pub fn find(game: &Game) -> Option<Self> {
+        if game.status() != GameStatus::Playing {
+            return None;
+        }
+        let constraints = constraints(game);
+        Self::deduce(&constraints)
+            .or_else(|| Self::deduce_subsets(&constraints))
+            .or_else(|| Self::guess(game, &constraints))
+    }
+
+    fn deduce(constraints: &[Constraint]) -> Option<Self> {
+        constraints.iter().find_map(|c| {
+            let (action, reason) = if c.bombs == 0 {
+                (
+                    BotAction::Reveal,
+                    BotReason::Satisfied { counter: c.counter },
+                )
+            } else if c.bombs == c.unknown.len() {
+                (BotAction::Flag, BotReason::Saturated { counter: c.counter })
+            } else {
+                return None;
+            };
+            Some(Self {
+                action,
+                coords: c.unknown[0],
+                reason,
+            })
+        })
+    }
+
+    fn deduce_subsets(constraints: &[Constraint]) -> Option<Self> {
+        for counter in constraints {
+            for subset in constraints {
+                // Only close counters can share neighbors
+                let close = counter.counter.x.abs_diff(subset.counter.x) <= 2
+                    && counter.counter.y.abs_diff(subset.counter.y) <= 2;
+                if !close
+                    || subset.unknown.len() >= counter.unknown.len()
+                    || subset.bombs > counter.bombs
+                    || !subset.unknown.iter().all(|c| counter.unknown.contains(c))
+                {
+                    continue;
+                }
+                let rest: Vec<Coordinates> = counter
+                    .unknown
+                    .iter()
+                    .filter(|c| !subset.unknown.contains(c))
+                    .copied()
+                    .collect();
+                let bombs = counter.bombs - subset.bombs;
+                let action = if bombs == 0 {
+                    BotAction::Reveal
+                } else if bombs == rest.len() {
+                    BotAction::Flag
+                } else {
+                    continue;
+                };
+                return Some(Self {
+                    action,
+                    coords: rest[0],
+                    reason: BotReason::Subset {
+                        counter: counter.counter,
+                        subset: subset.counter,
+                    },
+                });
+            }
+        }
+        None
+    }
+
+    fn guess(game: &Game, constraints: &[Constraint]) -> Option<Self> {
+        let unknown: Vec<Coordinates> = game
+            .tile_map()
+            .coordinates()
+            .filter(|c| game.is_covered(*c) && !game.is_flagged(*c))
+            .collect();
+        let flags = game.flags().count();
+        let bombs_left = (game.tile_map().bomb_count() as usize).saturating_sub(flags);
+        // Tiles away from any counter only know the global bomb density
+        let density = bombs_left as f32 / unknown.len().max(1) as f32;
+        unknown
+            .into_iter()
+            .map(|coords| {
+                let probability = constraints
+                    .iter()
+                    .filter(|c| c.unknown.contains(&coords))
+                    .map(|c| c.bombs as f32 / c.unknown.len() as f32)
+                    .reduce(f32::max)
+                    .unwrap_or(density);
+                (coords, probability)
+            })
+            // The first lowest probability tile is kept, for reproducible games
+            .fold(
+                None,
+                |best: Option<(Coordinates, f32)>, (coords, p)| match best {
+                    Some((_, best_p)) if best_p <= p => best,
+                    _ => Some((coords, p)),
+                },
+            )
+            .map(|(coords, probability)| Self {
+                action: BotAction::Reveal,
+                coords,
+                reason: BotReason::Guess { probability },
+            })
+    }
+}
+
+/// Retrieves the constraint of every uncovered bomb neighbor with unflagged covered neighbors
+fn constraints(game: &Game) -> Vec<Constraint> {
+    let tile_map = game.tile_map();
+    game.uncovered()
+        .filter_map(|counter| {
+            let count = match tile_map[counter.y as usize][counter.x as usize] {
+                Tile::BombNeighbor(v) => v as usize,
+                _ => return None,
+            };
+            let (flagged, unknown): (Vec<Coordinates>, Vec<Coordinates>) = tile_map
+                .safe_square_at(counter)
+                .filter(|c| tile_map.contains(*c) && game.is_covered(*c))
+                .partition(|c| game.is_flagged(*c));
+            // Wrong flags can leave a negative count, the counter is then ignored
+            let bombs = count.checked_sub(flagged.len())?;
+            (!unknown.is_empty() && bombs <= unknown.len()).then_some(Constraint {
+                counter,
+                unknown,
+                bombs,
+            })
+        })
+        .collect()
+}
+
+impl Display for BotMove {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.action {
+            BotAction::Reveal => write!(f, "uncover {}: ", self.coords)?,
+            BotAction::Flag => write!(f, "flag {}: ", self.coords)?,
+        }
+        match self.reason {
+            BotReason::Satisfied { counter } => {
+                write!(f, "every bomb around {} is flagged", counter)
+            }
+            BotReason::Saturated { counter } => {
+                write!(f, "{} has as many bombs left as covered neighbors", counter)
+            }
+            BotReason::Subset { counter, subset } => write!(
+                f,
+                "the bombs around {} leave a known count around {}",
+                subset, counter
+            ),
+            BotReason::Guess { probability } => write!(
+                f,
+                "no deduction left, lowest bomb probability is {:.0}%",
+                probability * 100.
+            ),
+        }
+    }
+}
@@ -1,10 +1,17 @@
-use bevy::prelude::Component;
+use serde::{Deserialize, Serialize};
 
 use std::fmt::{self, Display, Formatter};
 use std::ops::{Add, Sub};
 
-#[cfg_attr(feature = "debug", derive(bevy_inspector_egui::Inspectable))]
-#[derive(Debug, Copy, Default, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
+/// Tile coordinates, from the bottom left of the map
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
+#[cfg_attr(
+    feature = "bevy-inspector-egui",
+    derive(bevy_inspector_egui::Inspectable)
+)]
+#[derive(
+    Debug, Copy, Default, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub struct Coordinates {
     pub x: u16,
     pub y: u16,
@@ -0,0 +1,192 @@
+use crate::{GameOptions, GamePreset, GameStatus};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// Calendar day, in UTC
+#[derive(
+    Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default, Serialize, Deserialize,
+)]
+pub struct Date {
+    pub year: i32,
+    /// Month, from 1 to 12
+    pub month: u8,
+    /// Day of the month, from 1
+    pub day: u8,
+}
+
+impl Date {
+    /// Day of a unix timestamp, in seconds
+    #[must_use]
+    pub fn from_unix_time(seconds: i64) -> Self {
+        Self::from_days(seconds.div_euclid(86_400))
+    }
+
+    /// Day at `days` days from 1970-01-01
+    #[must_use]
+    pub fn from_days(days: i64) -> Self {
+        // Civil from days, with eras of 400 years starting on March 1st
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u8;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        } as u8;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+
+    /// Number of days from 1970-01-01
+    #[must_use]
+    pub fn days(self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+        let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The day before
+    #[must_use]
+    pub fn previous(self) -> Self {
+        Self::from_days(self.days() - 1)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Daily challenge, every player gets the same board on the same day
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DailyChallenge {
+    pub date: Date,
+}
+
+impl DailyChallenge {
+    /// Board size of every daily challenge
+    pub const PRESET: GamePreset = GamePreset::Intermediate;
+
+    /// Bomb placement seed of the day, mixed so consecutive days get unrelated boards
+    #[must_use]
+    pub fn seed(self) -> u64 {
+        // SplitMix64 finalizer
+        let mut z = (self.date.days() as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Game options of the day, the preset options with the seed of the day
+    #[must_use]
+    pub fn options(self) -> GameOptions {
+        GameOptions {
+            seed: Some(self.seed()),
+            ..Self::PRESET.options()
+        }
+    }
+}
+
+/// Scored attempt of a daily challenge
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub date: Date,
+    /// Attempt outcome, an attempt left while `Playing` is not won
+    pub status: GameStatus,
+    /// Play time, in seconds
+    pub time: f32,
+    /// Percentage of safe tiles uncovered
+    pub cleared: f32,
+}
+
+/// Daily challenge results, one per day
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyHistory {
+    /// Results by increasing date
+    pub results: Vec<DailyResult>,
+}
+
+impl DailyHistory {
+    /// Result of the day
+    #[must_use]
+    pub fn result(&self, date: Date) -> Option<&DailyResult> {
+        self.results.iter().rev().find(|r| r.date == date)
+    }
+
+    /// Starts the scored attempt of the day, returns `false` if it was already started.
+    ///
+    /// The attempt is recorded right away, leaving the game doesn't give another one
+    pub fn start(&mut self, date: Date) -> bool {
+        if self.result(date).is_some() {
+            return false;
+        }
+        self.results.push(DailyResult {
+            date,
+            status: GameStatus::Playing,
+            time: 0.,
+            cleared: 0.,
+        });
+        self.results.sort_by_key(|r| r.date);
+        true
+    }
+
+    /// Records the outcome of the scored attempt of the day
+    pub fn finish(&mut self, date: Date, status: GameStatus, time: f32, cleared: f32) {
+        if let Some(result) = self.results.iter_mut().rev().find(|r| r.date == date) {
+            result.status = status;
+            result.time = time;
+            result.cleared = cleared;
+        }
+    }
+
+    /// Number of consecutive won days, up to `today`.
+    ///
+    /// A streak is kept until the end of the day after the last win
+    #[must_use]
+    pub fn streak(&self, today: Date) -> u32 {
+        let won = |date: Date| {
+            self.result(date)
+                .is_some_and(|r| r.status == GameStatus::Won)
+        };
+        let mut date = if won(today) { today } else { today.previous() };
+        let mut streak = 0;
+        while won(date) {
+            streak += 1;
+            date = date.previous();
+        }
+        streak
+    }
+
+    /// Result of the day as text to share, with the current streak
+    #[must_use]
+    pub fn share_text(&self, date: Date) -> Option<String> {
+        let result = self.result(date)?;
+        let outcome = match result.status {
+            GameStatus::Won => format!("cleared in {:.1}s", result.time),
+            GameStatus::Lost => format!("exploded at {:.0}%", result.cleared),
+            GameStatus::Playing => format!("left at {:.0}%", result.cleared),
+        };
+        Some(format!(
+            "Minesweeper daily {} ({}): {} - streak {}",
+            date,
+            DailyChallenge::PRESET,
+            outcome,
+            self.streak(date)
+        ))
+    }
+}
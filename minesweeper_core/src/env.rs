@@ -0,0 +1,196 @@
+use crate::{Coordinates, Game, GameOptions, GameStatus, MoveError, Tile};
+
+/// Observed state of a covered tile
+pub const CELL_COVERED: i8 = -1;
+/// Observed state of a flagged tile
+pub const CELL_FLAGGED: i8 = -2;
+/// Observed state of an uncovered bomb
+pub const CELL_EXPLODED: i8 = -3;
+
+/// Number of one hot channels of an observation: bomb counts 0 to 8, covered, flagged and exploded
+pub const OBSERVATION_CHANNELS: usize = 12;
+
+/// Move played by an agent
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EnvAction {
+    Reveal(Coordinates),
+    Flag(Coordinates),
+    Chord(Coordinates),
+}
+
+impl EnvAction {
+    /// Decodes a discrete action index, `kind * width * height + y * width + x` where `kind`
+    /// is 0 to reveal, 1 to flag and 2 to chord
+    #[must_use]
+    pub fn from_index(index: usize, (width, height): (u16, u16)) -> Option<Self> {
+        let tiles = width as usize * height as usize;
+        let tile = index % tiles;
+        let coords = Coordinates {
+            x: (tile % width as usize) as u16,
+            y: (tile / width as usize) as u16,
+        };
+        match index / tiles {
+            0 => Some(Self::Reveal(coords)),
+            1 => Some(Self::Flag(coords)),
+            2 => Some(Self::Chord(coords)),
+            _ => None,
+        }
+    }
+}
+
+/// Visible state of every tile, row major from the bottom left.
+///
+/// Uncovered tiles hold their bomb count, other tiles a `CELL_*` constant
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Observation {
+    pub width: u16,
+    pub height: u16,
+    pub cells: Vec<i8>,
+}
+
+impl Observation {
+    fn new(game: &Game) -> Self {
+        let tile_map = game.tile_map();
+        let cells = tile_map
+            .coordinates()
+            .map(|c| {
+                if game.is_flagged(c) {
+                    CELL_FLAGGED
+                } else if game.is_covered(c) {
+                    CELL_COVERED
+                } else {
+                    match tile_map[c.y as usize][c.x as usize] {
+                        Tile::Bomb => CELL_EXPLODED,
+                        Tile::BombNeighbor(v) => v as i8,
+                        Tile::Empty => 0,
+                    }
+                }
+            })
+            .collect();
+        Self {
+            width: tile_map.width(),
+            height: tile_map.height(),
+            cells,
+        }
+    }
+
+    /// One hot tensor of shape `[OBSERVATION_CHANNELS, height, width]`
+    #[must_use]
+    pub fn one_hot(&self) -> Vec<f32> {
+        let tiles = self.cells.len();
+        let mut tensor = vec![0.; OBSERVATION_CHANNELS * tiles];
+        for (i, cell) in self.cells.iter().enumerate() {
+            let channel = match *cell {
+                CELL_COVERED => 9,
+                CELL_FLAGGED => 10,
+                CELL_EXPLODED => 11,
+                v => v as usize,
+            };
+            tensor[channel * tiles + i] = 1.;
+        }
+        tensor
+    }
+}
+
+/// Step rewards
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Rewards {
+    /// Reward for clearing the board
+    pub win: f32,
+    /// Reward for uncovering a bomb
+    pub loss: f32,
+    /// Reward for clearing the whole board, split between the steps by uncovered safe tiles
+    pub progress: f32,
+    /// Reward for a refused move or a move without effect
+    pub invalid: f32,
+}
+
+impl Default for Rewards {
+    fn default() -> Self {
+        Self {
+            win: 1.,
+            loss: -1.,
+            progress: 1.,
+            invalid: -0.1,
+        }
+    }
+}
+
+/// Gym style environment for agent training, playing a `Game` without any rendering.
+///
+/// An episode ends on the first explosion or once the board is cleared
+#[derive(Debug, Clone, Default)]
+pub struct Env {
+    pub rewards: Rewards,
+    game: Option<Game>,
+}
+
+impl Env {
+    #[must_use]
+    pub fn new(rewards: Rewards) -> Self {
+        Self {
+            rewards,
+            game: None,
+        }
+    }
+
+    /// Starts an episode on the game generated by `seed` and the other `options`
+    pub fn reset(&mut self, seed: u64, options: &GameOptions) -> Observation {
+        let game = Game::new(&GameOptions {
+            seed: Some(seed),
+            ..options.clone()
+        });
+        let observation = Observation::new(&game);
+        self.game = Some(game);
+        observation
+    }
+
+    /// Plays `action`, returning the new observation, the reward and if the episode is over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no episode was started with `reset`
+    pub fn step(&mut self, action: EnvAction) -> (Observation, f32, bool) {
+        let rewards = self.rewards;
+        let game = self.game.as_mut().expect("No episode started, call reset");
+        if game.status() != GameStatus::Playing {
+            return (Observation::new(game), 0., true);
+        }
+        let tile_map = game.tile_map();
+        let safe_tiles = (tile_map.width() as usize * tile_map.height() as usize)
+            .saturating_sub(tile_map.bomb_count() as usize)
+            .max(1);
+        let result: Result<usize, MoveError> = match action {
+            EnvAction::Reveal(coords) => game.reveal(coords).map(|r| r.len()),
+            EnvAction::Chord(coords) => game.chord(coords).map(|r| r.len()),
+            EnvAction::Flag(coords) => game.toggle_flag(coords).map(|_| 1),
+        };
+        let status = game.status();
+        let reward = match (result, status) {
+            (Ok(0) | Err(_), _) => rewards.invalid,
+            (Ok(_), GameStatus::Lost) => rewards.loss,
+            (Ok(changed), status) => {
+                let mut reward = 0.;
+                if !matches!(action, EnvAction::Flag(_)) {
+                    reward += rewards.progress * changed as f32 / safe_tiles as f32;
+                }
+                if status == GameStatus::Won {
+                    reward += rewards.win;
+                }
+                reward
+            }
+        };
+        (
+            Observation::new(game),
+            reward,
+            status != GameStatus::Playing,
+        )
+    }
+
+    /// The game of the current episode
+    #[inline]
+    #[must_use]
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+}
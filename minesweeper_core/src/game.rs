@@ -0,0 +1,315 @@
+use crate::{Coordinates, Tile, TileMap};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+
+/// Game generation options
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameOptions {
+    /// Tile map size
+    pub map_size: (u16, u16),
+    /// Bomb count
+    pub bomb_count: u16,
+    /// Does the game start with a safe place uncovered
+    pub safe_start: bool,
+    /// Bomb placement seed, games with the same seed and options are identical.
+    /// A random map is generated if none is set
+    pub seed: Option<u64>,
+}
+
+/// Game progression
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+pub enum GameStatus {
+    /// Safe tiles remain covered and no bomb exploded
+    #[default]
+    Playing,
+    /// Every safe tile is uncovered without any explosion
+    Won,
+    /// A bomb exploded
+    Lost,
+}
+
+/// Reason for refusing a move
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MoveError {
+    /// The coordinates are outside of the map
+    OutOfBounds(Coordinates),
+    /// Every safe tile is already uncovered
+    Finished,
+    /// The tile is already uncovered
+    Uncovered(Coordinates),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds(coords) => write!(f, "tile {} is outside of the board", coords),
+            Self::Finished => write!(f, "the board is already completed"),
+            Self::Uncovered(coords) => write!(f, "tile {} is already uncovered", coords),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Minesweeper game, applies the player moves to a `TileMap`.
+///
+/// Uncovering a bomb loses the game but the remaining safe tiles can still be uncovered,
+/// the game is over once they all are
+#[derive(Debug, Clone)]
+pub struct Game {
+    tile_map: TileMap,
+    covered: HashSet<Coordinates>,
+    flags: HashSet<Coordinates>,
+    explosions: Vec<Coordinates>,
+    claims: Vec<Coordinates>,
+}
+
+impl Game {
+    /// Generates a game matching `options`
+    #[must_use]
+    pub fn new(options: &GameOptions) -> Self {
+        Self::from_tile_map(TileMap::generate(options), options.safe_start)
+    }
+
+    /// Starts a game on `tile_map`, uncovering its safe start if `safe_start` is set
+    #[must_use]
+    pub fn from_tile_map(tile_map: TileMap, safe_start: bool) -> Self {
+        let covered = tile_map.coordinates().collect();
+        let mut game = Self {
+            tile_map,
+            covered,
+            flags: HashSet::new(),
+            explosions: Vec::new(),
+            claims: Vec::new(),
+        };
+        if safe_start {
+            if let Some(coords) = game.tile_map.safe_start() {
+                game.cascade(coords);
+            }
+        }
+        game
+    }
+
+    fn check(&self, coords: Coordinates) -> Result<(), MoveError> {
+        if !self.tile_map.contains(coords) {
+            Err(MoveError::OutOfBounds(coords))
+        } else if self.is_completed() {
+            Err(MoveError::Finished)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn tile(&self, coords: Coordinates) -> Tile {
+        self.tile_map[coords.y as usize][coords.x as usize]
+    }
+
+    /// Uncovers a tile and propagates through empty tiles, returning the uncovered tiles
+    fn cascade(&mut self, coords: Coordinates) -> Vec<Coordinates> {
+        let mut revealed = Vec::new();
+        let mut queue = vec![coords];
+        while let Some(coords) = queue.pop() {
+            if !self.covered.remove(&coords) {
+                continue;
+            }
+            revealed.push(coords);
+            self.flags.remove(&coords);
+            match self.tile(coords) {
+                Tile::Bomb => self.explosions.push(coords),
+                Tile::Empty => queue.extend(
+                    self.tile_map
+                        .safe_square_at(coords)
+                        .filter(|c| self.covered.contains(c)),
+                ),
+                Tile::BombNeighbor(_) => (),
+            }
+        }
+        revealed
+    }
+
+    /// Uncovers an unflagged tile, returning the uncovered tiles.
+    ///
+    /// Uncovering an empty tile uncovers its neighbors, flagged or not, until bomb neighbors are met
+    pub fn reveal(&mut self, coords: Coordinates) -> Result<Vec<Coordinates>, MoveError> {
+        self.check(coords)?;
+        if self.flags.contains(&coords) {
+            return Ok(Vec::new());
+        }
+        Ok(self.cascade(coords))
+    }
+
+    /// Uncovers a single tile, removing its flag, without any propagation.
+    ///
+    /// Used to mirror a game played elsewhere, returns `false` if the tile was already uncovered
+    pub fn reveal_tile(&mut self, coords: Coordinates) -> Result<bool, MoveError> {
+        if !self.tile_map.contains(coords) {
+            return Err(MoveError::OutOfBounds(coords));
+        }
+        if !self.covered.remove(&coords) {
+            return Ok(false);
+        }
+        self.flags.remove(&coords);
+        if self.tile(coords).is_bomb() {
+            self.explosions.push(coords);
+        }
+        Ok(true)
+    }
+
+    /// Uncovers a bomb without exploding it, like the claims of competitive games.
+    ///
+    /// Bombs can be claimed once every safe tile is uncovered, returns `false` if the tile was
+    /// already uncovered or is not a bomb
+    pub fn claim(&mut self, coords: Coordinates) -> Result<bool, MoveError> {
+        if !self.tile_map.contains(coords) {
+            return Err(MoveError::OutOfBounds(coords));
+        }
+        if !self.tile(coords).is_bomb() || !self.covered.remove(&coords) {
+            return Ok(false);
+        }
+        self.flags.remove(&coords);
+        self.claims.push(coords);
+        Ok(true)
+    }
+
+    /// Uncovers the unflagged neighbors of an uncovered bomb neighbor whose bombs are all flagged,
+    /// returning the uncovered tiles
+    pub fn chord(&mut self, coords: Coordinates) -> Result<Vec<Coordinates>, MoveError> {
+        self.check(coords)?;
+        Ok(self
+            .tiles_to_chord(coords)
+            .into_iter()
+            .flat_map(|c| self.cascade(c))
+            .collect())
+    }
+
+    /// Retrieves the tiles a chord on `coords` would uncover, without uncovering them
+    #[must_use]
+    pub fn tiles_to_chord(&self, coords: Coordinates) -> Vec<Coordinates> {
+        if !self.tile_map.contains(coords) || self.covered.contains(&coords) {
+            return Vec::new();
+        }
+        let count = match self.tile(coords) {
+            Tile::BombNeighbor(count) => count as usize,
+            _ => return Vec::new(),
+        };
+        let (flagged, unflagged): (Vec<Coordinates>, Vec<Coordinates>) = self
+            .tile_map
+            .safe_square_at(coords)
+            .filter(|c| self.covered.contains(c))
+            .partition(|c| self.flags.contains(c));
+        if flagged.len() == count {
+            unflagged
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Flags or unflags a covered tile, returning if the tile is now flagged
+    pub fn toggle_flag(&mut self, coords: Coordinates) -> Result<bool, MoveError> {
+        self.check(coords)?;
+        if !self.covered.contains(&coords) {
+            return Err(MoveError::Uncovered(coords));
+        }
+        if self.flags.remove(&coords) {
+            Ok(false)
+        } else {
+            self.flags.insert(coords);
+            Ok(true)
+        }
+    }
+
+    /// The generated map
+    #[inline]
+    #[must_use]
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    /// Is the tile at `coords` covered
+    #[inline]
+    #[must_use]
+    pub fn is_covered(&self, coords: Coordinates) -> bool {
+        self.covered.contains(&coords)
+    }
+
+    /// Is the tile at `coords` flagged
+    #[inline]
+    #[must_use]
+    pub fn is_flagged(&self, coords: Coordinates) -> bool {
+        self.flags.contains(&coords)
+    }
+
+    /// Covered tiles
+    pub fn covered(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.covered.iter().copied()
+    }
+
+    /// Number of covered tiles
+    #[inline]
+    #[must_use]
+    pub fn covered_count(&self) -> usize {
+        self.covered.len()
+    }
+
+    /// Uncovered tiles
+    pub fn uncovered(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.tile_map
+            .coordinates()
+            .filter(|c| !self.covered.contains(c))
+    }
+
+    /// Flagged tiles
+    pub fn flags(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.flags.iter().copied()
+    }
+
+    /// Uncovered bombs
+    #[inline]
+    #[must_use]
+    pub fn explosions(&self) -> &[Coordinates] {
+        &self.explosions
+    }
+
+    /// Claimed bombs, uncovered without exploding
+    #[inline]
+    #[must_use]
+    pub fn claims(&self) -> &[Coordinates] {
+        &self.claims
+    }
+
+    /// Percentage of safe tiles uncovered
+    #[must_use]
+    pub fn cleared(&self) -> f32 {
+        let tiles = self.tile_map.width() as usize * self.tile_map.height() as usize;
+        let safe_tiles = tiles - self.tile_map.bomb_count() as usize;
+        if safe_tiles == 0 {
+            return 100.;
+        }
+        (safe_tiles - self.covered_safe_tiles()) as f32 * 100. / safe_tiles as f32
+    }
+
+    fn covered_safe_tiles(&self) -> usize {
+        self.covered.len() + self.explosions.len() + self.claims.len()
+            - self.tile_map.bomb_count() as usize
+    }
+
+    /// Are all the safe tiles uncovered
+    #[inline]
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.covered_safe_tiles() == 0
+    }
+
+    /// Current game progression
+    #[must_use]
+    pub fn status(&self) -> GameStatus {
+        if !self.explosions.is_empty() {
+            GameStatus::Lost
+        } else if self.is_completed() {
+            GameStatus::Won
+        } else {
+            GameStatus::Playing
+        }
+    }
+}
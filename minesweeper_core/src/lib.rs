@@ -0,0 +1,25 @@
+//! Engine independent minesweeper rules.
+//!
+//! A `Game` holds a generated `TileMap` and the state of every tile, and applies the player moves,
+//! `BotMove` chooses the moves of a bot player, and `Env` exposes games to agent training.
+//! `DailyChallenge` derives the board of the day and `DailyHistory` keeps the daily results.
+//! `BoardCode` encodes a board as text, to play the same board elsewhere, and `PuzzlePack` holds
+//! the puzzles of a puzzle file, with `PuzzleProgress` keeping the player results.
+//! `SpeedrunCourse` chains boards for time attacks, and `SpeedrunRecords` keeps the best splits.
+//! The `bevy` feature makes `Coordinates` a component for engine frontends
+pub use {
+    board_code::*, bot::*, coordinates::*, daily::*, env::*, game::*, preset::*, puzzle::*,
+    speedrun::*, tile::*, tile_map::*,
+};
+
+mod board_code;
+mod bot;
+mod coordinates;
+mod daily;
+mod env;
+mod game;
+mod preset;
+mod puzzle;
+mod speedrun;
+mod tile;
+mod tile_map;
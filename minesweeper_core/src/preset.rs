@@ -0,0 +1,59 @@
+use crate::GameOptions;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Standard game sizes shared by every frontend
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub enum GamePreset {
+    /// 9x9 map with 10 bombs
+    Beginner,
+    /// 16x16 map with 40 bombs
+    #[default]
+    Intermediate,
+    /// 30x16 map with 99 bombs
+    Expert,
+}
+
+impl GamePreset {
+    /// Every preset, by increasing difficulty
+    pub const ALL: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    /// Game options of the preset, with a safe start and a random seed
+    #[must_use]
+    pub const fn options(self) -> GameOptions {
+        let (map_size, bomb_count) = match self {
+            Self::Beginner => ((9, 9), 10),
+            Self::Intermediate => ((16, 16), 40),
+            Self::Expert => ((30, 16), 99),
+        };
+        GameOptions {
+            map_size,
+            bomb_count,
+            safe_start: true,
+            seed: None,
+        }
+    }
+}
+
+impl Display for GamePreset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Beginner => "beginner",
+            Self::Intermediate => "intermediate",
+            Self::Expert => "expert",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for GamePreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Unknown preset {}", s))
+    }
+}
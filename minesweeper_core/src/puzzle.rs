@@ -0,0 +1,205 @@
+use crate::{Coordinates, GameOptions, TileMap};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Bombs of a puzzle board
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PuzzleBoard {
+    /// Hand-made board rows from the top, with `*` for bombs and `.` for safe tiles
+    Rows(Vec<String>),
+    /// Generated board
+    Seed {
+        map_size: (u16, u16),
+        bomb_count: u16,
+        seed: u64,
+    },
+}
+
+/// Puzzle of a puzzle pack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    pub board: PuzzleBoard,
+    /// Tile uncovered when the puzzle starts, from the bottom left.
+    /// Puzzles without start cell start fully covered
+    #[serde(default)]
+    pub start: Option<Coordinates>,
+    /// Time to beat, in seconds
+    #[serde(default)]
+    pub target_time: Option<f32>,
+}
+
+/// Puzzle pack file, holding several puzzles
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PuzzlePack {
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    pub puzzles: Vec<Puzzle>,
+}
+
+/// Reason for rejecting a puzzle board
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PuzzleError {
+    /// The board has no tile
+    Empty,
+    /// A row isn't as wide as the first one
+    UnevenRow {
+        row: usize,
+        width: usize,
+        expected: usize,
+    },
+    /// A tile is neither `*` nor `.`
+    InvalidTile {
+        row: usize,
+        column: usize,
+        tile: char,
+    },
+    /// Every tile is a bomb
+    NoSafeTile,
+    /// The start cell is outside the board or on a bomb
+    InvalidStart(Coordinates),
+}
+
+impl Display for PuzzleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the board has no tile"),
+            Self::UnevenRow {
+                row,
+                width,
+                expected,
+            } => write!(
+                f,
+                "row {} is {} tiles wide instead of {}",
+                row + 1,
+                width,
+                expected
+            ),
+            Self::InvalidTile { row, column, tile } => write!(
+                f,
+                "invalid tile '{}' at row {}, column {}, expected '*' or '.'",
+                tile,
+                row + 1,
+                column + 1
+            ),
+            Self::NoSafeTile => write!(f, "every tile is a bomb"),
+            Self::InvalidStart(coords) => {
+                write!(
+                    f,
+                    "the start cell {} is not a safe tile of the board",
+                    coords
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {}
+
+impl Puzzle {
+    /// Puzzle of the bombs of `tile_map`, without start cell or target time
+    #[must_use]
+    pub fn new(title: impl Into<String>, tile_map: &TileMap) -> Self {
+        Self {
+            title: title.into(),
+            author: String::new(),
+            board: PuzzleBoard::Rows(tile_map.rows()),
+            start: None,
+            target_time: None,
+        }
+    }
+
+    /// Generates the tile map of the puzzle, checking its start cell
+    pub fn tile_map(&self) -> Result<TileMap, PuzzleError> {
+        let tile_map = match &self.board {
+            PuzzleBoard::Rows(rows) => TileMap::from_rows(rows)?,
+            PuzzleBoard::Seed {
+                map_size,
+                bomb_count,
+                seed,
+            } => {
+                let tiles = u32::from(map_size.0) * u32::from(map_size.1);
+                if tiles == 0 {
+                    return Err(PuzzleError::Empty);
+                }
+                if u32::from(*bomb_count) >= tiles {
+                    return Err(PuzzleError::NoSafeTile);
+                }
+                TileMap::generate(&GameOptions {
+                    map_size: *map_size,
+                    bomb_count: *bomb_count,
+                    safe_start: false,
+                    seed: Some(*seed),
+                })
+            }
+        };
+        match self.start {
+            Some(start) if !tile_map.contains(start) || tile_map.is_bomb_at(start) => {
+                Err(PuzzleError::InvalidStart(start))
+            }
+            _ => Ok(tile_map),
+        }
+    }
+}
+
+/// Best result of a puzzle
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleRecord {
+    /// Number of started games
+    pub attempts: u32,
+    /// Best solving time in seconds, `None` until solved
+    pub best_time: Option<f32>,
+}
+
+/// Puzzle results, by pack and puzzle title
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PuzzleProgress {
+    pub records: BTreeMap<String, BTreeMap<String, PuzzleRecord>>,
+}
+
+impl PuzzleProgress {
+    /// Result of a puzzle of a pack
+    #[must_use]
+    pub fn record(&self, pack: &str, puzzle: &str) -> Option<&PuzzleRecord> {
+        self.records.get(pack)?.get(puzzle)
+    }
+
+    fn record_mut(&mut self, pack: &str, puzzle: &str) -> &mut PuzzleRecord {
+        self.records
+            .entry(pack.to_string())
+            .or_default()
+            .entry(puzzle.to_string())
+            .or_default()
+    }
+
+    /// Counts a started game
+    pub fn start(&mut self, pack: &str, puzzle: &str) {
+        self.record_mut(pack, puzzle).attempts += 1;
+    }
+
+    /// Records a solving time, returns `true` if it is the best one
+    pub fn solve(&mut self, pack: &str, puzzle: &str, time: f32) -> bool {
+        let record = self.record_mut(pack, puzzle);
+        if record.best_time.is_some_and(|best| best <= time) {
+            return false;
+        }
+        record.best_time = Some(time);
+        true
+    }
+
+    /// Number of solved puzzles of `pack`
+    #[must_use]
+    pub fn solved_count(&self, pack: &PuzzlePack) -> usize {
+        pack.puzzles
+            .iter()
+            .filter(|p| {
+                self.record(&pack.title, &p.title)
+                    .is_some_and(|r| r.best_time.is_some())
+            })
+            .count()
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{GameOptions, GamePreset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Fixed sequence of boards, played in a row against the clock
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedrunCourse {
+    /// Course name, personal bests are kept by name
+    pub name: String,
+    pub boards: Vec<GameOptions>,
+}
+
+impl SpeedrunCourse {
+    /// Course of `preset` boards, one per seed
+    #[must_use]
+    pub fn seeded(
+        name: impl Into<String>,
+        preset: GamePreset,
+        seeds: impl IntoIterator<Item = u64>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            boards: seeds
+                .into_iter()
+                .map(|seed| GameOptions {
+                    seed: Some(seed),
+                    ..preset.options()
+                })
+                .collect(),
+        }
+    }
+
+    /// Five seeded beginner boards
+    #[must_use]
+    pub fn beginner() -> Self {
+        Self::seeded("beginner-5", GamePreset::Beginner, 1..=5)
+    }
+}
+
+/// Personal best splits of the speedrun courses, by course name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpeedrunRecords {
+    /// Cumulative time at the end of every board of the best run, in seconds
+    pub best_splits: BTreeMap<String, Vec<f32>>,
+}
+
+impl SpeedrunRecords {
+    /// Splits of the best run of a course
+    #[must_use]
+    pub fn best(&self, course: &str) -> Option<&[f32]> {
+        self.best_splits.get(course).map(Vec::as_slice)
+    }
+
+    /// Difference between a cumulative time and the same split of the best run, negative when ahead
+    #[must_use]
+    pub fn delta(&self, course: &str, split: usize, time: f32) -> Option<f32> {
+        self.best(course)?.get(split).map(|best| time - best)
+    }
+
+    /// Records the splits of a run, returns `true` if it is the new best run.
+    ///
+    /// Only runs clearing every board of the course are recorded
+    pub fn finish(&mut self, course: &SpeedrunCourse, splits: &[f32]) -> bool {
+        let time = match splits.last() {
+            Some(t) if splits.len() == course.boards.len() => *t,
+            _ => return false,
+        };
+        let best = self.best(&course.name).and_then(|s| s.last());
+        if best.is_some_and(|best| *best <= time) {
+            return false;
+        }
+        self.best_splits
+            .insert(course.name.clone(), splits.to_vec());
+        true
+    }
+}
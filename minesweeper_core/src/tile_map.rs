@@ -0,0 +1,283 @@
+use crate::{Coordinates, GameOptions, PuzzleError, Tile};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::ops::{Deref, DerefMut};
+
+/// Delta coordinates for all 8 square neighbors
+/// Bottom left | Bottom | Bottom right
+/// Left        |        | Right
+/// Top Left    |  Top   | Top right
+/// *--------*-------*-------*
+/// | -1, 1  | 0, 1  | 1, 1  |
+/// |--------|-------|-------|
+/// | -1, 0  | tile  | 1, 0  |
+/// |--------|-------|-------|
+/// | -1, -1 | 0, -1 | 1, -1 |
+/// *--------*-------*-------*
+const SQUARE_COORDINATES: [(i8, i8); 8] = [
+    // Bottom left
+    (-1, -1),
+    // Bottom
+    (0, -1),
+    // Bottom right
+    (1, -1),
+    // Left
+    (-1, 0),
+    // Right
+    (1, 0),
+    // Top Left
+    (-1, 1),
+    // Top
+    (0, 1),
+    // Top right
+    (1, 1),
+];
+
+/// Base tile map
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    bomb_count: u16,
+    height: u16,
+    width: u16,
+    map: Vec<Vec<Tile>>,
+}
+
+impl TileMap {
+    /// Generates an empty map
+    #[inline]
+    #[must_use]
+    pub fn empty(width: u16, height: u16) -> Self {
+        let map = vec![vec![Tile::Empty; width as usize]; height as usize];
+        Self {
+            bomb_count: 0,
+            height,
+            width,
+            map,
+        }
+    }
+
+    /// Generates a map matching `options`, boards generated with the same seed are identical
+    #[must_use]
+    pub fn generate(options: &GameOptions) -> Self {
+        // `StdRng` may change algorithm between rand versions, ChaCha12 is its rand 0.8 algorithm
+        let mut rng = match options.seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_entropy(),
+        };
+        let mut tile_map = Self::empty(options.map_size.0, options.map_size.1);
+        tile_map.set_bombs(options.bomb_count, &mut rng);
+        tile_map
+    }
+
+    /// Generates a hand-made map with bombs on the given coordinates, and their bomb neighbors
+    #[must_use]
+    pub fn with_bombs(
+        width: u16,
+        height: u16,
+        bombs: impl IntoIterator<Item = Coordinates>,
+    ) -> Self {
+        let mut tile_map = Self::empty(width, height);
+        for coords in bombs {
+            if tile_map.contains(coords) {
+                tile_map[coords.y as usize][coords.x as usize] = Tile::Bomb;
+            }
+        }
+        tile_map.set_bomb_neighbors();
+        tile_map
+    }
+
+    /// Generates a hand-made map from its rows, from the top, with `*` for bombs and `.` for
+    /// safe tiles
+    pub fn from_rows<S: AsRef<str>>(rows: &[S]) -> Result<Self, PuzzleError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.as_ref().chars().count());
+        if width == 0 {
+            return Err(PuzzleError::Empty);
+        }
+        let mut bombs = Vec::new();
+        for (row, line) in rows.iter().enumerate() {
+            let line = line.as_ref();
+            if line.chars().count() != width {
+                return Err(PuzzleError::UnevenRow {
+                    row,
+                    width: line.chars().count(),
+                    expected: width,
+                });
+            }
+            for (column, tile) in line.chars().enumerate() {
+                match tile {
+                    '*' => bombs.push(Coordinates {
+                        x: column as u16,
+                        y: (height - 1 - row) as u16,
+                    }),
+                    '.' => (),
+                    _ => return Err(PuzzleError::InvalidTile { row, column, tile }),
+                }
+            }
+        }
+        if bombs.len() == width * height {
+            return Err(PuzzleError::NoSafeTile);
+        }
+        Ok(Self::with_bombs(width as u16, height as u16, bombs))
+    }
+
+    /// Rows of the map, from the top, with `*` for bombs and `.` for safe tiles
+    #[must_use]
+    pub fn rows(&self) -> Vec<String> {
+        self.iter()
+            .rev()
+            .map(|line| {
+                line.iter()
+                    .map(|tile| if tile.is_bomb() { '*' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Retrieves the first empty tile, used as a safe place to start
+    #[must_use]
+    pub fn safe_start(&self) -> Option<Coordinates> {
+        self.iter().enumerate().find_map(|(y, line)| {
+            line.iter()
+                .position(|tile| *tile == Tile::Empty)
+                .map(|x| Coordinates {
+                    x: x as u16,
+                    y: y as u16,
+                })
+        })
+    }
+
+    /// Places bombs and bomb neighbor tiles, drawing bomb positions from `rng`
+    pub fn set_bombs<R: Rng>(&mut self, bomb_count: u16, rng: &mut R) {
+        self.bomb_count = bomb_count;
+        let mut remaining_bombs = bomb_count;
+        // Place bombs
+        while remaining_bombs > 0 {
+            let (x, y) = (
+                rng.gen_range(0..self.width) as usize,
+                rng.gen_range(0..self.height) as usize,
+            );
+            if self[y][x] == Tile::Empty {
+                self[y][x] = Tile::Bomb;
+                remaining_bombs -= 1;
+            }
+        }
+        self.set_bomb_neighbors();
+    }
+
+    /// Recomputes the bomb count and every bomb neighbor tile from the bombs of the map.
+    ///
+    /// Maps edited through `DerefMut` must call it once their bombs are placed
+    pub fn set_bomb_neighbors(&mut self) {
+        let mut bomb_count = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coords = Coordinates { x, y };
+                if self.is_bomb_at(coords) {
+                    bomb_count += 1;
+                    continue;
+                }
+                let tile = match self.bomb_count_at(coords) {
+                    0 => Tile::Empty,
+                    num => Tile::BombNeighbor(num),
+                };
+                self[y as usize][x as usize] = tile;
+            }
+        }
+        self.bomb_count = bomb_count;
+    }
+
+    /// Are the `coordinates` inside the map
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, coordinates: Coordinates) -> bool {
+        coordinates.x < self.width && coordinates.y < self.height
+    }
+
+    /// Coordinates of every tile, in row-major order from the bottom left
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coordinates { x, y }))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_bomb_at(&self, coordinates: Coordinates) -> bool {
+        self.contains(coordinates)
+            && self.map[coordinates.y as usize][coordinates.x as usize].is_bomb()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn bomb_count_at(&self, coordinates: Coordinates) -> u8 {
+        if self.is_bomb_at(coordinates) {
+            return 0;
+        }
+        let res = self
+            .safe_square_at(coordinates)
+            .filter(|coord| self.is_bomb_at(*coord))
+            .count();
+        res as u8
+    }
+
+    #[inline]
+    pub fn safe_square_at(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
+        SQUARE_COORDINATES
+            .iter()
+            .copied()
+            .map(move |tuple| coordinates + tuple)
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs:\n",
+            self.width, self.height, self.bomb_count
+        );
+        let line: String = (0..=(self.width + 1)).map(|_| '-').collect();
+        buffer = format!("{}{}\n", buffer, line);
+        for line in self.iter().rev() {
+            buffer = format!("{}|", buffer);
+            for tile in line.iter() {
+                buffer = format!("{}{}", buffer, tile.console_output());
+            }
+            buffer = format!("{}|\n", buffer);
+        }
+        format!("{}{}", buffer, line)
+    }
+
+    // Getter for `width`
+    #[inline]
+    #[must_use]
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    // Getter for `height`
+    #[inline]
+    #[must_use]
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    // Getter for `bomb_count`
+    #[inline]
+    #[must_use]
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+}
+
+impl Deref for TileMap {
+    type Target = Vec<Vec<Tile>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl DerefMut for TileMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
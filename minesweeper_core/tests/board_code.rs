@@ -0,0 +1,132 @@
+use minesweeper_core::{
+    BoardCode, BoardCodeError, BoardLayout, Coordinates, Game, GameOptions, TileMap,
+};
+
+#[test]
+fn seeded_codes_round_trip() {
+    let code: BoardCode = "MS1-20x20-50-3fa9c2".parse().unwrap();
+    assert_eq!(code.map_size, (20, 20));
+    assert_eq!(code.bomb_count, 50);
+    assert!(code.safe_start);
+    assert_eq!(code.layout, BoardLayout::Seed(0x3f_a9c2));
+    assert_eq!(code.to_string(), "MS1-20x20-50-3fa9c2");
+
+    let options = GameOptions {
+        map_size: (30, 16),
+        bomb_count: 99,
+        safe_start: false,
+        seed: Some(u64::MAX),
+    };
+    let code = BoardCode::seeded(&options).unwrap();
+    assert_eq!(code.to_string(), "MS1-30x16-99n-ffffffffffffffff");
+    assert_eq!(code.to_string().parse::<BoardCode>(), Ok(code.clone()));
+    // The code plays the exact same board
+    assert_eq!(**code.game().tile_map(), **Game::new(&options).tile_map());
+    assert_eq!(
+        BoardCode::seeded(&GameOptions {
+            seed: None,
+            ..options
+        }),
+        None
+    );
+}
+
+#[test]
+fn layout_codes_round_trip() {
+    let bombs = [Coordinates { x: 1, y: 0 }, Coordinates { x: 4, y: 2 }];
+    let tile_map = TileMap::with_bombs(5, 3, bombs);
+    let code = BoardCode::from_tile_map(&tile_map, false);
+    // 15 tiles, bombs on the tiles 1 and 14
+    assert_eq!(code.to_string(), "MS1-5x3-2n-L4002");
+    let decoded: BoardCode = "ms1-5X3-2n-l4002".parse().unwrap();
+    assert_eq!(decoded, code);
+    assert_eq!(**decoded.tile_map(), *tile_map);
+}
+
+#[test]
+fn start_cells_round_trip() {
+    let tile_map = TileMap::with_bombs(5, 3, [Coordinates { x: 1, y: 0 }]);
+    let mut code = BoardCode::from_tile_map(&tile_map, false);
+    code.start = Some(Coordinates { x: 4, y: 2 });
+    assert_eq!(code.to_string(), "MS1-5x3-1n-L4000@4,2");
+    assert_eq!(code.to_string().parse::<BoardCode>(), Ok(code.clone()));
+    // The board starts uncovered from the start cell
+    assert!(!code.game().is_covered(Coordinates { x: 4, y: 2 }));
+
+    let error = |code: &str| code.parse::<BoardCode>().unwrap_err();
+    let invalid = |start: &str| BoardCodeError::InvalidStart(start.to_string());
+    assert_eq!(error("MS1-5x3-1n-L4000@4"), invalid("4"));
+    assert_eq!(error("MS1-5x3-1n-L4000@5,0"), invalid("5,0"));
+    // Hand-made boards can't start on a bomb
+    assert_eq!(error("MS1-5x3-1n-L4000@1,0"), invalid("1,0"));
+    assert_eq!(
+        "MS1-20x20-50-3fa9c2@0,19"
+            .parse::<BoardCode>()
+            .unwrap()
+            .start,
+        Some(Coordinates { x: 0, y: 19 })
+    );
+}
+
+#[test]
+fn bad_codes_are_rejected() {
+    let error = |code: &str| code.parse::<BoardCode>().unwrap_err();
+    assert_eq!(error("hello"), BoardCodeError::NotABoardCode);
+    assert_eq!(error("MSx-20x20-50-3fa9c2"), BoardCodeError::NotABoardCode);
+    assert_eq!(
+        error("MS2-20x20-50-3fa9c2"),
+        BoardCodeError::UnsupportedVersion(2)
+    );
+    assert_eq!(error("MS1-20x20-50"), BoardCodeError::MissingParts(3));
+    assert_eq!(
+        error("MS1-20-50-3fa9c2"),
+        BoardCodeError::InvalidSize("20".to_string())
+    );
+    assert_eq!(
+        error("MS1-0x20-50-3fa9c2"),
+        BoardCodeError::InvalidSize("0x20".to_string())
+    );
+    assert_eq!(
+        error("MS1-20x20-lots-3fa9c2"),
+        BoardCodeError::InvalidBombCount("lots".to_string())
+    );
+    assert_eq!(
+        error("MS1-3x3-9-1"),
+        BoardCodeError::TooManyBombs { bombs: 9, tiles: 9 }
+    );
+    assert_eq!(
+        error("MS1-20x20-50-zz"),
+        BoardCodeError::InvalidSeed("zz".to_string())
+    );
+    assert_eq!(error("MS1-5x3-2n-L400"), BoardCodeError::InvalidLayout);
+    assert_eq!(error("MS1-5x3-2n-L400g"), BoardCodeError::InvalidLayout);
+    // The 16th bit is padding
+    assert_eq!(error("MS1-5x3-2n-L4003"), BoardCodeError::InvalidLayout);
+    assert_eq!(
+        error("MS1-5x3-3n-L4002"),
+        BoardCodeError::BombCountMismatch {
+            expected: 3,
+            found: 2
+        }
+    );
+    assert_eq!(
+        error("MS2-1x1-0-0").to_string(),
+        "board code version 2 is not supported, update the game"
+    );
+}
+
+#[test]
+fn oversized_codes_are_rejected() {
+    for (code, width, height) in [
+        ("MS1-65535x65535-1-0", 65535, 65535),
+        ("MS1-300x300-100-0", 300, 300),
+        ("MS1-257x10-10-0", 257, 10),
+    ] {
+        assert_eq!(
+            code.parse::<BoardCode>(),
+            Err(BoardCodeError::TooLarge { width, height })
+        );
+    }
+    let code: BoardCode = "MS1-256x256-100-0".parse().unwrap();
+    assert_eq!(code.map_size, (BoardCode::MAX_SIDE, BoardCode::MAX_SIDE));
+}
@@ -0,0 +1,58 @@
+use minesweeper_core::{BotAction, BotMove, BotReason, Game, GameOptions, GamePreset, GameStatus};
+
+fn play(options: &GameOptions) -> Game {
+    let mut game = Game::new(options);
+    while let Some(bot_move) = BotMove::find(&game) {
+        match bot_move.action {
+            BotAction::Reveal => game.reveal(bot_move.coords).unwrap(),
+            BotAction::Flag => {
+                // Deduced flags are always right
+                assert!(game.tile_map().is_bomb_at(bot_move.coords), "{}", bot_move);
+                game.toggle_flag(bot_move.coords)
+                    .map(|_| Vec::new())
+                    .unwrap()
+            }
+        };
+    }
+    game
+}
+
+#[test]
+fn deductions_never_hit_a_bomb() {
+    for seed in 0..50 {
+        let mut game = Game::new(&GameOptions {
+            seed: Some(seed),
+            ..GamePreset::Intermediate.options()
+        });
+        while let Some(bot_move) = BotMove::find(&game) {
+            if matches!(bot_move.reason, BotReason::Guess { .. }) {
+                break;
+            }
+            match bot_move.action {
+                BotAction::Reveal => {
+                    assert!(!game.tile_map().is_bomb_at(bot_move.coords), "{}", bot_move);
+                    game.reveal(bot_move.coords).unwrap();
+                }
+                BotAction::Flag => {
+                    assert!(game.tile_map().is_bomb_at(bot_move.coords), "{}", bot_move);
+                    game.toggle_flag(bot_move.coords).unwrap();
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn bot_wins_most_beginner_games() {
+    let games = 200;
+    let wins = (0..games)
+        .map(|seed| {
+            play(&GameOptions {
+                seed: Some(seed),
+                ..GamePreset::Beginner.options()
+            })
+        })
+        .filter(|game| game.status() == GameStatus::Won)
+        .count();
+    assert!(wins * 10 >= games as usize * 8, "won {}/{}", wins, games);
+}
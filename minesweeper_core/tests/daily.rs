@@ -0,0 +1,79 @@
+use minesweeper_core::{DailyChallenge, DailyHistory, Date, Game, GameStatus};
+
+fn date(year: i32, month: u8, day: u8) -> Date {
+    Date { year, month, day }
+}
+
+#[test]
+fn dates_convert_from_unix_time() {
+    assert_eq!(Date::from_unix_time(0), date(1970, 1, 1));
+    assert_eq!(Date::from_unix_time(951_782_400), date(2000, 2, 29));
+    assert_eq!(Date::from_unix_time(1_792_367_999), date(2026, 10, 18));
+    assert_eq!(Date::from_unix_time(-1), date(1969, 12, 31));
+    for days in -1000..1000 {
+        assert_eq!(Date::from_days(days).days(), days);
+    }
+    assert_eq!(date(2024, 3, 1).previous(), date(2024, 2, 29));
+    assert_eq!(date(2026, 10, 18).to_string(), "2026-10-18");
+}
+
+#[test]
+fn daily_boards_are_shared_by_day() {
+    let today = DailyChallenge {
+        date: date(2026, 10, 18),
+    };
+    let tomorrow = DailyChallenge {
+        date: date(2026, 10, 19),
+    };
+    assert_eq!(today.options(), today.options());
+    assert_ne!(today.seed(), tomorrow.seed());
+    assert_eq!(
+        **Game::new(&today.options()).tile_map(),
+        **Game::new(&today.options()).tile_map()
+    );
+    assert_ne!(
+        **Game::new(&today.options()).tile_map(),
+        **Game::new(&tomorrow.options()).tile_map()
+    );
+    let options = DailyChallenge::PRESET.options();
+    assert_eq!(today.options().map_size, options.map_size);
+    assert_eq!(today.options().bomb_count, options.bomb_count);
+}
+
+#[test]
+fn one_scored_attempt_per_day() {
+    let mut history = DailyHistory::default();
+    let today = date(2026, 10, 18);
+    assert!(history.start(today));
+    assert!(!history.start(today));
+    assert_eq!(history.result(today).unwrap().status, GameStatus::Playing);
+    history.finish(today, GameStatus::Won, 61.25, 100.);
+    assert_eq!(history.result(today).unwrap().status, GameStatus::Won);
+    assert!(!history.start(today));
+    assert_eq!(history.results.len(), 1);
+}
+
+#[test]
+fn streaks_count_consecutive_wins() {
+    let mut history = DailyHistory::default();
+    for (day, status) in [
+        (14, GameStatus::Won),
+        (15, GameStatus::Lost),
+        (16, GameStatus::Won),
+        (17, GameStatus::Won),
+    ] {
+        history.start(date(2026, 10, day));
+        history.finish(date(2026, 10, day), status, 90., 50.);
+    }
+    // Today is not played yet
+    assert_eq!(history.streak(date(2026, 10, 18)), 2);
+    assert_eq!(history.streak(date(2026, 10, 19)), 0);
+    history.start(date(2026, 10, 18));
+    history.finish(date(2026, 10, 18), GameStatus::Won, 42.04, 100.);
+    assert_eq!(history.streak(date(2026, 10, 18)), 3);
+    assert_eq!(
+        history.share_text(date(2026, 10, 18)).unwrap(),
+        "Minesweeper daily 2026-10-18 (intermediate): cleared in 42.0s - streak 3"
+    );
+    assert_eq!(history.share_text(date(2026, 10, 20)), None);
+}
@@ -0,0 +1,96 @@
+use minesweeper_core::{
+    BotAction, BotMove, Coordinates, Env, EnvAction, Game, GameOptions, GamePreset, CELL_COVERED,
+    CELL_EXPLODED, CELL_FLAGGED, OBSERVATION_CHANNELS,
+};
+
+fn options() -> GameOptions {
+    GamePreset::Beginner.options()
+}
+
+#[test]
+fn reset_observes_the_safe_start() {
+    let mut env = Env::default();
+    let observation = env.reset(3, &options());
+    let game = Game::new(&GameOptions {
+        seed: Some(3),
+        ..options()
+    });
+    assert_eq!((observation.width, observation.height), (9, 9));
+    let covered = observation
+        .cells
+        .iter()
+        .filter(|c| **c == CELL_COVERED)
+        .count();
+    assert_eq!(covered, game.covered_count());
+    assert!(covered < 81);
+    let tensor = observation.one_hot();
+    assert_eq!(tensor.len(), OBSERVATION_CHANNELS * 81);
+    assert_eq!(tensor.iter().sum::<f32>(), 81.);
+}
+
+#[test]
+fn step_rewards_and_ends_episodes() {
+    let mut env = Env::default();
+    env.reset(4, &options());
+    let game = env.game().unwrap();
+    let bomb = game
+        .covered()
+        .find(|c| game.tile_map().is_bomb_at(*c))
+        .unwrap();
+    let index = (bomb.y * 9 + bomb.x) as usize;
+
+    let (observation, reward, done) = env.step(EnvAction::Flag(bomb));
+    assert_eq!(observation.cells[index], CELL_FLAGGED);
+    assert_eq!((reward, done), (0., false));
+    // Flagged tiles can't be uncovered
+    let (_, reward, done) = env.step(EnvAction::Reveal(bomb));
+    assert_eq!((reward, done), (env.rewards.invalid, false));
+
+    env.step(EnvAction::Flag(bomb));
+    let (observation, reward, done) = env.step(EnvAction::Reveal(bomb));
+    assert_eq!(observation.cells[index], CELL_EXPLODED);
+    assert_eq!((reward, done), (env.rewards.loss, true));
+}
+
+#[test]
+fn bot_episodes_are_played_to_the_end() {
+    let mut env = Env::default();
+    for seed in 0..100 {
+        env.reset(seed, &options());
+        let mut total = 0.;
+        loop {
+            let bot_move = BotMove::find(env.game().unwrap()).unwrap();
+            let action = match bot_move.action {
+                BotAction::Reveal => EnvAction::Reveal(bot_move.coords),
+                BotAction::Flag => EnvAction::Flag(bot_move.coords),
+            };
+            let (_, reward, done) = env.step(action);
+            total += reward;
+            if done {
+                break;
+            }
+        }
+        if env.game().unwrap().is_completed() && env.game().unwrap().explosions().is_empty() {
+            // Progress covers the tiles uncovered after the safe start
+            assert!(total > env.rewards.win && total <= env.rewards.win + env.rewards.progress);
+        }
+    }
+}
+
+#[test]
+fn action_indices_decode_kind_and_tile() {
+    let size = (9, 9);
+    assert_eq!(
+        EnvAction::from_index(4, size),
+        Some(EnvAction::Reveal(Coordinates { x: 4, y: 0 }))
+    );
+    assert_eq!(
+        EnvAction::from_index(81 + 9 + 2, size),
+        Some(EnvAction::Flag(Coordinates { x: 2, y: 1 }))
+    );
+    assert_eq!(
+        EnvAction::from_index(2 * 81 + 80, size),
+        Some(EnvAction::Chord(Coordinates { x: 8, y: 8 }))
+    );
+    assert_eq!(EnvAction::from_index(3 * 81, size), None);
+}
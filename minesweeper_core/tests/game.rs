@@ -0,0 +1,183 @@
+use minesweeper_core::{Coordinates, Game, GameOptions, GameStatus, MoveError, Tile, TileMap};
+
+fn options(seed: u64) -> GameOptions {
+    GameOptions {
+        map_size: (16, 16),
+        bomb_count: 40,
+        safe_start: true,
+        seed: Some(seed),
+    }
+}
+
+fn safe_tiles(game: &Game) -> Vec<Coordinates> {
+    game.covered()
+        .filter(|c| !game.tile_map().is_bomb_at(*c))
+        .collect()
+}
+
+fn bomb(game: &Game) -> Coordinates {
+    game.covered()
+        .find(|c| game.tile_map().is_bomb_at(*c))
+        .unwrap()
+}
+
+#[test]
+fn same_seed_generates_same_game() {
+    for seed in 0..20 {
+        let a = Game::new(&options(seed));
+        let b = Game::new(&options(seed));
+        assert_eq!(**a.tile_map(), **b.tile_map());
+        let mut covered_a: Vec<_> = a.covered().collect();
+        let mut covered_b: Vec<_> = b.covered().collect();
+        covered_a.sort();
+        covered_b.sort();
+        assert_eq!(covered_a, covered_b);
+    }
+}
+
+#[test]
+fn safe_start_uncovers_an_empty_area() {
+    for seed in 0..20 {
+        let game = Game::new(&options(seed));
+        assert_eq!(game.status(), GameStatus::Playing);
+        assert!(game.explosions().is_empty());
+        let start = game.tile_map().safe_start().unwrap();
+        assert!(!game.is_covered(start));
+    }
+}
+
+#[test]
+fn cascade_stops_at_bomb_neighbors() {
+    for seed in 0..20 {
+        let game = Game::new(&options(seed));
+        let tile_map = game.tile_map();
+        for coords in game.uncovered() {
+            assert!(!tile_map.is_bomb_at(coords));
+            if tile_map[coords.y as usize][coords.x as usize] == Tile::Empty {
+                assert!(tile_map
+                    .safe_square_at(coords)
+                    .filter(|c| tile_map.contains(*c))
+                    .all(|c| !game.is_covered(c)));
+            }
+        }
+    }
+}
+
+#[test]
+fn uncovering_a_bomb_loses() {
+    let mut game = Game::new(&options(1));
+    let bomb = bomb(&game);
+    assert_eq!(game.reveal(bomb), Ok(vec![bomb]));
+    assert_eq!(game.status(), GameStatus::Lost);
+    assert_eq!(game.explosions(), &[bomb]);
+    // Safe tiles can still be uncovered
+    let tile = safe_tiles(&game)[0];
+    assert!(game.reveal(tile).unwrap().contains(&tile));
+}
+
+#[test]
+fn flags_protect_tiles() {
+    let mut game = Game::new(&options(2));
+    let bomb = bomb(&game);
+    assert_eq!(game.toggle_flag(bomb), Ok(true));
+    assert!(game.is_flagged(bomb));
+    assert_eq!(game.reveal(bomb), Ok(Vec::new()));
+    assert!(game.is_covered(bomb));
+    assert_eq!(game.toggle_flag(bomb), Ok(false));
+    assert!(!game.is_flagged(bomb));
+
+    let start = game.tile_map().safe_start().unwrap();
+    assert_eq!(game.toggle_flag(start), Err(MoveError::Uncovered(start)));
+}
+
+#[test]
+fn chord_requires_every_bomb_flagged() {
+    let mut game = Game::new(&options(3));
+    // An uncovered bomb neighbor with covered neighbors
+    let tile_map = game.tile_map().clone();
+    let (coords, neighbors) = game
+        .uncovered()
+        .filter(|c| matches!(tile_map[c.y as usize][c.x as usize], Tile::BombNeighbor(_)))
+        .map(|c| {
+            let neighbors: Vec<_> = tile_map
+                .safe_square_at(c)
+                .filter(|n| game.is_covered(*n))
+                .collect();
+            (c, neighbors)
+        })
+        .find(|(_, n)| n.iter().any(|c| !tile_map.is_bomb_at(*c)))
+        .unwrap();
+    assert_eq!(game.chord(coords), Ok(Vec::new()));
+    for neighbor in neighbors.iter().filter(|c| tile_map.is_bomb_at(**c)) {
+        game.toggle_flag(*neighbor).unwrap();
+    }
+    let revealed = game.chord(coords).unwrap();
+    assert!(!revealed.is_empty());
+    assert!(revealed.iter().all(|c| !tile_map.is_bomb_at(*c)));
+    assert_eq!(game.status(), GameStatus::Playing);
+}
+
+#[test]
+fn uncovering_every_safe_tile_wins() {
+    let mut game = Game::new(&options(4));
+    while let Some(tile) = safe_tiles(&game).first() {
+        game.reveal(*tile).unwrap();
+    }
+    assert!(game.is_completed());
+    assert_eq!(game.status(), GameStatus::Won);
+    assert_eq!(game.cleared(), 100.);
+    assert_eq!(game.reveal(bomb(&game)), Err(MoveError::Finished));
+}
+
+#[test]
+fn claimed_bombs_do_not_explode() {
+    let mut game = Game::new(&options(4));
+    while let Some(tile) = safe_tiles(&game).first() {
+        game.reveal(*tile).unwrap();
+    }
+    let bomb = bomb(&game);
+    assert_eq!(game.claim(bomb), Ok(true));
+    assert_eq!(game.claim(bomb), Ok(false));
+    assert!(!game.is_covered(bomb));
+    assert_eq!(game.claims(), [bomb]);
+    assert!(game.explosions().is_empty());
+    assert!(game.is_completed());
+    assert_eq!(game.status(), GameStatus::Won);
+}
+
+#[test]
+fn out_of_bounds_moves_are_refused() {
+    let mut game = Game::new(&options(5));
+    let coords = Coordinates { x: 16, y: 0 };
+    assert_eq!(game.reveal(coords), Err(MoveError::OutOfBounds(coords)));
+    assert_eq!(
+        game.toggle_flag(coords),
+        Err(MoveError::OutOfBounds(coords))
+    );
+    assert_eq!(game.chord(coords), Err(MoveError::OutOfBounds(coords)));
+}
+
+#[test]
+fn mirrored_tiles_are_uncovered_without_propagation() {
+    let mut game = Game::new(&GameOptions {
+        safe_start: false,
+        ..options(6)
+    });
+    let start = game.tile_map().safe_start().unwrap();
+    assert_eq!(game.reveal_tile(start), Ok(true));
+    assert_eq!(game.covered_count(), 16 * 16 - 1);
+    assert_eq!(game.reveal_tile(start), Ok(false));
+}
+
+#[test]
+fn hand_made_maps_count_bomb_neighbors() {
+    let bombs = [Coordinates { x: 1, y: 0 }, Coordinates { x: 1, y: 0 }];
+    let tile_map = TileMap::with_bombs(5, 4, bombs);
+    assert_eq!(tile_map.bomb_count(), 1);
+    assert_eq!(tile_map[0][1], Tile::Bomb);
+    assert_eq!(tile_map[1][2], Tile::BombNeighbor(1));
+    assert_eq!(tile_map[3][4], Tile::Empty);
+    let game = Game::from_tile_map(tile_map, true);
+    assert!(game.is_covered(Coordinates { x: 0, y: 0 }));
+    assert_eq!(game.covered_count(), 2);
+}
@@ -0,0 +1,115 @@
+use minesweeper_core::{
+    Coordinates, GameOptions, Puzzle, PuzzleBoard, PuzzleError, PuzzlePack, PuzzleProgress, Tile,
+    TileMap,
+};
+
+#[test]
+fn puzzles_round_trip_their_rows() {
+    let rows = ["..*.", "....", "*..."];
+    let tile_map = TileMap::from_rows(&rows).unwrap();
+    assert_eq!((tile_map.width(), tile_map.height()), (4, 3));
+    assert_eq!(tile_map.bomb_count(), 2);
+    // Rows are from the top, coordinates from the bottom
+    assert!(tile_map.is_bomb_at(Coordinates { x: 2, y: 2 }));
+    assert!(tile_map.is_bomb_at(Coordinates { x: 0, y: 0 }));
+    assert_eq!(tile_map.rows(), rows);
+
+    let puzzle = Puzzle::new("corners", &tile_map);
+    assert_eq!(**puzzle.tile_map().unwrap(), **tile_map);
+}
+
+#[test]
+fn puzzles_check_their_board_and_start() {
+    let mut puzzle = Puzzle {
+        title: "seeded".to_string(),
+        author: String::new(),
+        board: PuzzleBoard::Seed {
+            map_size: (9, 9),
+            bomb_count: 10,
+            seed: 7,
+        },
+        start: None,
+        target_time: Some(30.),
+    };
+    let generated = TileMap::generate(&GameOptions {
+        map_size: (9, 9),
+        bomb_count: 10,
+        safe_start: false,
+        seed: Some(7),
+    });
+    assert_eq!(**puzzle.tile_map().unwrap(), **generated);
+
+    let bomb = generated.coordinates().find(|c| generated.is_bomb_at(*c));
+    puzzle.start = bomb;
+    assert_eq!(
+        puzzle.tile_map().unwrap_err(),
+        PuzzleError::InvalidStart(bomb.unwrap())
+    );
+    puzzle.start = Some(Coordinates { x: 9, y: 0 });
+    assert!(puzzle.tile_map().is_err());
+    puzzle.board = PuzzleBoard::Seed {
+        map_size: (3, 3),
+        bomb_count: 9,
+        seed: 7,
+    };
+    assert_eq!(puzzle.tile_map().unwrap_err(), PuzzleError::NoSafeTile);
+}
+
+#[test]
+fn progress_keeps_best_times() {
+    let tile_map = TileMap::from_rows(&["*.."]).unwrap();
+    let pack = PuzzlePack {
+        title: "pack".to_string(),
+        author: String::new(),
+        puzzles: vec![Puzzle::new("a", &tile_map), Puzzle::new("b", &tile_map)],
+    };
+    let mut progress = PuzzleProgress::default();
+    progress.start("pack", "a");
+    assert_eq!(progress.solved_count(&pack), 0);
+    assert!(progress.solve("pack", "a", 12.));
+    assert!(!progress.solve("pack", "a", 15.));
+    assert!(progress.solve("pack", "a", 10.));
+    progress.start("pack", "a");
+    let record = progress.record("pack", "a").unwrap();
+    assert_eq!((record.attempts, record.best_time), (2, Some(10.)));
+    assert_eq!(progress.solved_count(&pack), 1);
+    assert!(progress.record("pack", "b").is_none());
+}
+
+#[test]
+fn invalid_puzzles_are_rejected() {
+    let empty: [&str; 0] = [];
+    assert_eq!(TileMap::from_rows(&empty).unwrap_err(), PuzzleError::Empty);
+    assert_eq!(
+        TileMap::from_rows(&["...", ".."]).unwrap_err(),
+        PuzzleError::UnevenRow {
+            row: 1,
+            width: 2,
+            expected: 3
+        }
+    );
+    assert_eq!(
+        TileMap::from_rows(&[".x."]).unwrap_err(),
+        PuzzleError::InvalidTile {
+            row: 0,
+            column: 1,
+            tile: 'x'
+        }
+    );
+    assert_eq!(
+        TileMap::from_rows(&["**"]).unwrap_err(),
+        PuzzleError::NoSafeTile
+    );
+}
+
+#[test]
+fn edited_maps_recompute_their_neighbors() {
+    let mut tile_map = TileMap::with_bombs(3, 3, [Coordinates { x: 0, y: 0 }]);
+    tile_map[0][0] = Tile::Empty;
+    tile_map[2][2] = Tile::Bomb;
+    tile_map.set_bomb_neighbors();
+    assert_eq!(tile_map.bomb_count(), 1);
+    assert_eq!(tile_map[0][0], Tile::Empty);
+    assert_eq!(tile_map[0][1], Tile::Empty);
+    assert_eq!(tile_map[1][1], Tile::BombNeighbor(1));
+}
@@ -0,0 +1,35 @@
+use minesweeper_core::{Game, GamePreset, SpeedrunCourse, SpeedrunRecords};
+
+#[test]
+fn courses_chain_distinct_seeded_boards() {
+    let course = SpeedrunCourse::beginner();
+    assert_eq!(course.boards.len(), 5);
+    let options = GamePreset::Beginner.options();
+    for board in &course.boards {
+        assert_eq!(board.map_size, options.map_size);
+        assert_eq!(board.bomb_count, options.bomb_count);
+    }
+    assert_eq!(course, SpeedrunCourse::beginner());
+    assert_ne!(
+        **Game::new(&course.boards[0]).tile_map(),
+        **Game::new(&course.boards[1]).tile_map()
+    );
+}
+
+#[test]
+fn records_keep_the_best_complete_run() {
+    let course = SpeedrunCourse::seeded("test", GamePreset::Beginner, [1, 2]);
+    let mut records = SpeedrunRecords::default();
+    assert_eq!(records.delta("test", 0, 5.), None);
+    // Runs ended by a death are not recorded
+    assert!(!records.finish(&course, &[4.]));
+    assert!(records.finish(&course, &[4., 10.]));
+    assert_eq!(records.delta("test", 0, 3.), Some(-1.));
+    assert_eq!(records.delta("test", 1, 12.), Some(2.));
+    assert_eq!(records.delta("test", 2, 12.), None);
+    // Slower runs keep the previous splits, even with a faster first board
+    assert!(!records.finish(&course, &[2., 11.]));
+    assert_eq!(records.best("test"), Some(&[4., 10.][..]));
+    assert!(records.finish(&course, &[5., 9.]));
+    assert_eq!(records.best("test"), Some(&[5., 9.][..]));
+}
@@ -0,0 +1,99 @@
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor, queue};
+use minesweeper_core::{Coordinates, GameStatus, Tile};
+use minesweeper_tui::session::Session;
+use std::io::{self, Write};
+
+const HELP: &str = "arrows/hjkl: move  space: uncover  f: flag  c: chord  r: restart  q: quit";
+
+/// Draws the whole screen: status line, board and help.
+///
+/// Lines are overwritten in place to avoid flickering on slow connections
+pub fn draw(out: &mut impl Write, session: &Session) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(0, 0))?;
+    let status = match session.status() {
+        GameStatus::Playing => "Playing",
+        GameStatus::Won => "You won !",
+        GameStatus::Lost => "Boom ! You lost",
+    };
+    queue!(
+        out,
+        Print(format!(
+            "Bombs: {:<4} Time: {:<5} Seed: {}  {}",
+            session.bombs_left(),
+            session.elapsed().as_secs(),
+            session.options.seed.unwrap_or_default(),
+            status
+        )),
+        Clear(ClearType::UntilNewLine)
+    )?;
+    let tile_map = session.game.tile_map();
+    for y in 0..tile_map.height() {
+        // The first map line is the bottom one, like on the graphical board
+        let row = 2 + tile_map.height() - 1 - y;
+        queue!(out, cursor::MoveTo(0, row))?;
+        for x in 0..tile_map.width() {
+            let coords = Coordinates { x, y };
+            if coords == session.cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            draw_tile(out, session, coords)?;
+            if coords == session.cursor {
+                queue!(out, SetAttribute(Attribute::NoReverse))?;
+            }
+        }
+    }
+    let row = 3 + tile_map.height();
+    queue!(
+        out,
+        cursor::MoveTo(0, row),
+        Print(session.message.as_deref().unwrap_or_default()),
+        Clear(ClearType::UntilNewLine)
+    )?;
+    queue!(out, cursor::MoveTo(0, row + 1), Print(HELP))?;
+    out.flush()
+}
+
+fn draw_tile(out: &mut impl Write, session: &Session, coords: Coordinates) -> io::Result<()> {
+    let game = &session.game;
+    let tile = game.tile_map()[coords.y as usize][coords.x as usize];
+    let lost = session.status() == GameStatus::Lost;
+    let (symbol, color) = if game.is_flagged(coords) {
+        // Wrong flags are shown once the game is lost
+        match (lost, tile.is_bomb()) {
+            (true, false) => ('X', Color::DarkRed),
+            _ => ('F', Color::Red),
+        }
+    } else if game.is_covered(coords) {
+        // Bombs are shown once the game is lost
+        match (lost, tile.is_bomb()) {
+            (true, true) => ('*', Color::Grey),
+            _ => ('.', Color::DarkGrey),
+        }
+    } else {
+        match tile {
+            Tile::Bomb => ('*', Color::Red),
+            Tile::BombNeighbor(v) => (char::from(b'0' + v), bomb_counter_color(v)),
+            Tile::Empty => (' ', Color::Reset),
+        }
+    };
+    queue!(
+        out,
+        SetForegroundColor(color),
+        Print(symbol),
+        ResetColor,
+        Print(' ')
+    )
+}
+
+/// Number colors, matching the graphical board counters
+fn bomb_counter_color(count: u8) -> Color {
+    match count {
+        1 => Color::White,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::DarkYellow,
+        _ => Color::Magenta,
+    }
+}
@@ -0,0 +1,2 @@
+//! Terminal minesweeper game state, rendered and driven by the `minesweeper_tui` binary
+pub mod session;
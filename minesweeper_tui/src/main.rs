@@ -0,0 +1,113 @@
+//! Terminal minesweeper, playable over SSH
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::{cursor, execute, terminal};
+use minesweeper_core::{GameOptions, GamePreset};
+use minesweeper_tui::session::{Direction, Session};
+use std::io::{self, Write};
+use std::time::Duration;
+
+mod draw;
+
+const USAGE: &str = "Usage: minesweeper_tui [--preset beginner|intermediate|expert] [--size WxH] [--bombs N] [--seed N] [--no-safe-start]";
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run(Session::new(options)) {
+        eprintln!("Terminal error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<GameOptions, String> {
+    let mut options = GamePreset::default().options();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--preset" => {
+                options = GameOptions {
+                    seed: options.seed,
+                    ..value()?.parse::<GamePreset>()?.options()
+                }
+            }
+            "--bombs" => options.bomb_count = value()?.parse().map_err(|e| format!("{}", e))?,
+            "--seed" => options.seed = Some(value()?.parse().map_err(|e| format!("{}", e))?),
+            "--size" => {
+                let size = value()?;
+                let (width, height) = size
+                    .split_once('x')
+                    .ok_or(format!("Invalid size {}", size))?;
+                options.map_size = (
+                    width.parse().map_err(|e| format!("{}", e))?,
+                    height.parse().map_err(|e| format!("{}", e))?,
+                );
+            }
+            "--no-safe-start" => options.safe_start = false,
+            _ => return Err(format!("Unknown argument {}", arg)),
+        }
+    }
+    let (width, height) = options.map_size;
+    if width == 0
+        || height == 0
+        || u32::from(options.bomb_count) >= u32::from(width) * u32::from(height)
+    {
+        return Err("The board needs at least one safe tile".to_string());
+    }
+    Ok(options)
+}
+
+/// Runs the game until the player quits, restoring the terminal even on error
+fn run(mut session: Session) -> io::Result<()> {
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+    let result = game_loop(&mut out, &mut session);
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn game_loop(out: &mut impl Write, session: &mut Session) -> io::Result<()> {
+    execute!(out, terminal::Clear(terminal::ClearType::All))?;
+    loop {
+        draw::draw(out, session)?;
+        // We redraw at least every second for the timer
+        if !event::poll(Duration::from_millis(250))? {
+            continue;
+        }
+        let key = match event::read()? {
+            Event::Key(key) => key,
+            Event::Resize(..) => {
+                execute!(out, terminal::Clear(terminal::ClearType::All))?;
+                continue;
+            }
+            _ => continue,
+        };
+        if !handle_key(session, key) {
+            return Ok(());
+        }
+    }
+}
+
+/// Applies a key press, returns `false` when the player quits
+fn handle_key(session: &mut Session, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+        KeyCode::Char('q') | KeyCode::Esc => return false,
+        KeyCode::Up | KeyCode::Char('k') => session.move_cursor(Direction::Up),
+        KeyCode::Down | KeyCode::Char('j') => session.move_cursor(Direction::Down),
+        KeyCode::Left | KeyCode::Char('h') => session.move_cursor(Direction::Left),
+        KeyCode::Right | KeyCode::Char('l') => session.move_cursor(Direction::Right),
+        KeyCode::Char(' ') | KeyCode::Enter => session.uncover(),
+        KeyCode::Char('f') => session.toggle_flag(),
+        KeyCode::Char('c') => session.chord(),
+        KeyCode::Char('r') => session.restart(),
+        _ => (),
+    }
+    true
+}
@@ -0,0 +1,114 @@
+use minesweeper_core::{Coordinates, Game, GameOptions, GameStatus, MoveError};
+use std::time::{Duration, Instant};
+
+/// Cursor movement direction
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// A terminal game: the core game, the cursor and the timer
+pub struct Session {
+    pub game: Game,
+    pub options: GameOptions,
+    pub cursor: Coordinates,
+    /// Set on the first move
+    started: Option<Instant>,
+    /// Set once the game is won or lost
+    finished: Option<Duration>,
+    /// Last refused move
+    pub message: Option<String>,
+}
+
+impl Session {
+    /// Starts a game, the options seed is replaced by a random one if none is set
+    pub fn new(mut options: GameOptions) -> Self {
+        // The seed is kept so it can be displayed and replayed
+        options.seed.get_or_insert_with(rand::random);
+        let game = Game::new(&options);
+        let cursor = game.tile_map().safe_start().unwrap_or_default();
+        Self {
+            game,
+            options,
+            cursor,
+            started: None,
+            finished: None,
+            message: None,
+        }
+    }
+
+    /// Starts a new game with the same options and a new seed
+    pub fn restart(&mut self) {
+        *self = Self::new(GameOptions {
+            seed: None,
+            ..self.options.clone()
+        });
+    }
+
+    /// Moves the cursor, staying on the map
+    pub fn move_cursor(&mut self, direction: Direction) {
+        let (width, height) = (self.game.tile_map().width(), self.game.tile_map().height());
+        let cursor = &mut self.cursor;
+        match direction {
+            // Rows are drawn from the top, the last row has y = 0
+            Direction::Up => cursor.y = (cursor.y + 1).min(height - 1),
+            Direction::Down => cursor.y = cursor.y.saturating_sub(1),
+            Direction::Left => cursor.x = cursor.x.saturating_sub(1),
+            Direction::Right => cursor.x = (cursor.x + 1).min(width - 1),
+        }
+    }
+
+    /// Uncovers the tile under the cursor, or chords it if it is already uncovered
+    pub fn uncover(&mut self) {
+        if self.game.is_covered(self.cursor) {
+            let cursor = self.cursor;
+            self.play(|game| game.reveal(cursor).map(drop));
+        } else {
+            self.chord();
+        }
+    }
+
+    /// Chords the tile under the cursor
+    pub fn chord(&mut self) {
+        let cursor = self.cursor;
+        self.play(|game| game.chord(cursor).map(drop));
+    }
+
+    /// Flags or unflags the tile under the cursor
+    pub fn toggle_flag(&mut self) {
+        let cursor = self.cursor;
+        self.play(|game| game.toggle_flag(cursor).map(drop));
+    }
+
+    fn play(&mut self, action: impl FnOnce(&mut Game) -> Result<(), MoveError>) {
+        // Unlike the core game, the terminal game stops at the first explosion
+        if self.status() != GameStatus::Playing {
+            return;
+        }
+        self.started.get_or_insert_with(Instant::now);
+        self.message = action(&mut self.game).err().map(|e| e.to_string());
+        if self.status() != GameStatus::Playing {
+            self.finished = Some(self.elapsed());
+        }
+    }
+
+    /// Current game progression
+    pub fn status(&self) -> GameStatus {
+        self.game.status()
+    }
+
+    /// Time spent since the first move, frozen once the game is over
+    pub fn elapsed(&self) -> Duration {
+        self.finished
+            .or_else(|| self.started.map(|s| s.elapsed()))
+            .unwrap_or_default()
+    }
+
+    /// Bombs left to flag
+    pub fn bombs_left(&self) -> i32 {
+        i32::from(self.game.tile_map().bomb_count()) - self.game.flags().count() as i32
+    }
+}
@@ -0,0 +1,45 @@
+use minesweeper_core::{Coordinates, GameOptions};
+use minesweeper_tui::session::{Direction, Session};
+
+fn session() -> Session {
+    let mut session = Session::new(GameOptions {
+        map_size: (4, 3),
+        bomb_count: 1,
+        safe_start: false,
+        seed: Some(1),
+    });
+    session.cursor = Coordinates { x: 0, y: 0 };
+    session
+}
+
+fn moved(session: &mut Session, directions: &[Direction]) -> (u16, u16) {
+    for direction in directions {
+        session.move_cursor(*direction);
+    }
+    (session.cursor.x, session.cursor.y)
+}
+
+#[test]
+fn cursor_moves_as_drawn() {
+    let mut session = session();
+    // The bottom row has y = 0, going up increases y
+    assert_eq!(moved(&mut session, &[Direction::Up]), (0, 1));
+    assert_eq!(moved(&mut session, &[Direction::Right]), (1, 1));
+    assert_eq!(moved(&mut session, &[Direction::Down]), (1, 0));
+    assert_eq!(moved(&mut session, &[Direction::Left]), (0, 0));
+}
+
+#[test]
+fn cursor_stays_on_the_map() {
+    let mut session = session();
+    assert_eq!(
+        moved(&mut session, &[Direction::Down, Direction::Left]),
+        (0, 0)
+    );
+    assert_eq!(moved(&mut session, &[Direction::Up; 5]), (0, 2));
+    assert_eq!(moved(&mut session, &[Direction::Right; 10]), (3, 2));
+    assert_eq!(
+        moved(&mut session, &[Direction::Up, Direction::Right]),
+        (3, 2)
+    );
+}
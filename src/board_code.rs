@@ -0,0 +1,309 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::minesweeper_core::BoardCode;
+use board_plugin::{Board, BoardOptions};
+
+use crate::buttons::ButtonAction;
+use crate::screens::Screen;
+use crate::{AppState, StateEvent};
+
+/// Board played from a code. Must be used as a resource, the code is played while it exists
+#[derive(Debug)]
+pub struct CodeRun {
+    /// Board options to restore when leaving the board
+    pub(crate) previous_options: BoardOptions,
+}
+
+/// Board code typed on the main menu, when the clipboard holds none. Must be used as a resource,
+/// the keyboard types the code while it exists
+#[derive(Debug, Default)]
+pub struct CodeEntry(String);
+
+impl CodeEntry {
+    fn prompt(&self, error: Option<String>) -> String {
+        let error = error.map_or(String::new(), |e| format!("{}\n", e));
+        format!(
+            "{}Type a board code, Enter to play, Escape to cancel\n> {}_",
+            error, self.0
+        )
+    }
+}
+
+/// Text of the board code panel
+#[derive(Debug, Component)]
+struct CodeMessage;
+
+/// System clipboard, kept open as some platforms drop the copied text with it
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct Clipboard(Option<arboard::Clipboard>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Clipboard {
+    fn get(&mut self) -> Result<&mut arboard::Clipboard, String> {
+        if self.0.is_none() {
+            self.0 = Some(arboard::Clipboard::new().map_err(|e| e.to_string())?);
+        }
+        Ok(self.0.as_mut().unwrap())
+    }
+
+    fn copy(&mut self, text: String) -> Result<(), String> {
+        self.get()?.set_text(text).map_err(|e| e.to_string())
+    }
+
+    fn paste(&mut self) -> Result<String, String> {
+        self.get()?.get_text().map_err(|e| e.to_string())
+    }
+}
+
+/// Browsers only give clipboard access to their own scripts
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+struct Clipboard;
+
+#[cfg(target_arch = "wasm32")]
+impl Clipboard {
+    fn copy(&mut self, _text: String) -> Result<(), String> {
+        Err("no clipboard in the browser".to_string())
+    }
+
+    fn paste(&mut self) -> Result<String, String> {
+        Err("no clipboard in the browser".to_string())
+    }
+}
+
+/// Registers the board code systems
+pub fn setup_board_code(app: &mut App) {
+    app.insert_non_send_resource(Clipboard::default())
+        .add_system(copy_code)
+        .add_system(play_code)
+        .add_system(type_code)
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_code))
+        .add_system_set(SystemSet::on_exit(AppState::MainMenu).with_system(end_code_entry))
+        .add_system_set(SystemSet::on_enter(AppState::Pause).with_system(show_code))
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(show_code))
+        .add_system_set(SystemSet::on_enter(AppState::Defeat).with_system(show_code));
+}
+
+/// Shows `text` in the board code panel, spawning it with the current screen
+fn show_message(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    messages: &mut Query<&mut Text, With<CodeMessage>>,
+    text: String,
+) {
+    if let Some(mut message) = messages.iter_mut().next() {
+        message.sections[0].value = text;
+        return;
+    }
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(10.),
+                    left: Val::Px(10.),
+                    ..Default::default()
+                },
+                padding: Rect::all(Val::Px(5.)),
+                ..Default::default()
+            },
+            color: Color::rgba(0.15, 0.15, 0.15, 0.85).into(),
+            ..Default::default()
+        })
+        // Removed with the screen
+        .insert(Screen)
+        .insert(Name::new("Board Code"))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        max_size: Size::new(Val::Px(640.), Val::Undefined),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        text,
+                        TextStyle {
+                            font: asset_server.load("fonts/pixeled.ttf"),
+                            font_size: 12.,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(CodeMessage);
+        });
+}
+
+/// Shows the code of the current board on the pause and end screens
+fn show_code(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut messages: Query<&mut Text, With<CodeMessage>>,
+    boards: Query<&Board>,
+) {
+    if let Some(board) = boards.iter().next() {
+        let text = format!("Board code: {}", board.code());
+        show_message(&mut commands, &asset_server, &mut messages, text);
+    }
+}
+
+/// Copies the code of the current board to the clipboard
+fn copy_code(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut clipboard: NonSendMut<Clipboard>,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut messages: Query<&mut Text, With<CodeMessage>>,
+    boards: Query<&Board>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked || *action != ButtonAction::CopyCode {
+            continue;
+        }
+        let code = match boards.iter().next() {
+            Some(b) => b.code().to_string(),
+            None => continue,
+        };
+        let text = match clipboard.copy(code.clone()) {
+            Ok(()) => format!("Copied {}", code),
+            Err(e) => {
+                log::error!("Failed to copy the board code: {}", e);
+                format!("Board code: {}\nCopy failed: {}", code, e)
+            }
+        };
+        show_message(&mut commands, &asset_server, &mut messages, text);
+    }
+}
+
+/// Plays the board of `code`, restoring the current options once it ends
+fn start_code(
+    commands: &mut Commands,
+    run: Option<&CodeRun>,
+    board_options: &mut BoardOptions,
+    state_wr: &mut EventWriter<StateEvent>,
+    code: &BoardCode,
+) {
+    log::info!("playing board code {}", code);
+    // A code played during another code run still returns to the options before the first one
+    if run.is_none() {
+        let previous_options = board_options.clone();
+        commands.insert_resource(CodeRun { previous_options });
+    }
+    board_options.apply_code(code);
+    state_wr.send(StateEvent(AppState::InGame));
+}
+
+/// Plays the board of the code in the clipboard, or lets the player type one
+#[allow(clippy::too_many_arguments)]
+fn play_code(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut clipboard: NonSendMut<Clipboard>,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut messages: Query<&mut Text, With<CodeMessage>>,
+    run: Option<Res<CodeRun>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked || *action != ButtonAction::PlayCode {
+            continue;
+        }
+        let code = clipboard
+            .paste()
+            .and_then(|text| text.parse::<BoardCode>().map_err(|e| e.to_string()));
+        match code {
+            Ok(code) => start_code(
+                &mut commands,
+                run.as_deref(),
+                &mut board_options,
+                &mut state_wr,
+                &code,
+            ),
+            // Browsers and remote terminals may have no clipboard
+            Err(e) => {
+                log::warn!("No board code in the clipboard: {}", e);
+                let entry = CodeEntry::default();
+                let text = entry.prompt(Some(format!("No code in the clipboard: {}", e)));
+                show_message(&mut commands, &asset_server, &mut messages, text);
+                commands.insert_resource(entry);
+            }
+        }
+    }
+}
+
+/// Types the board code of the code entry, and plays it on Enter
+#[allow(clippy::too_many_arguments)]
+fn type_code(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keys: Res<Input<KeyCode>>,
+    mut char_evr: EventReader<ReceivedCharacter>,
+    entry: Option<ResMut<CodeEntry>>,
+    mut messages: Query<&mut Text, With<CodeMessage>>,
+    run: Option<Res<CodeRun>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    let mut entry = match entry {
+        Some(e) => e,
+        None => return,
+    };
+    if keys.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<CodeEntry>();
+        let text = "Copy or type a board code to play it".to_string();
+        show_message(&mut commands, &asset_server, &mut messages, text);
+        return;
+    }
+    let mut error = None;
+    if keys.just_pressed(KeyCode::Return) {
+        match entry.0.parse::<BoardCode>() {
+            Ok(code) => {
+                commands.remove_resource::<CodeEntry>();
+                start_code(
+                    &mut commands,
+                    run.as_deref(),
+                    &mut board_options,
+                    &mut state_wr,
+                    &code,
+                );
+                return;
+            }
+            Err(e) => error = Some(format!("Invalid board code: {}", e)),
+        }
+    } else if keys.just_pressed(KeyCode::Back) {
+        entry.0.pop();
+    }
+    // Codes are plain ASCII, control characters come with their own keys
+    let typed: String = char_evr
+        .iter()
+        .map(|e| e.char)
+        .filter(char::is_ascii_graphic)
+        .collect();
+    if !typed.is_empty() {
+        entry.0.push_str(&typed);
+    }
+    if entry.is_changed() || error.is_some() {
+        let text = entry.prompt(error);
+        show_message(&mut commands, &asset_server, &mut messages, text);
+    }
+}
+
+/// Stops typing a code when leaving the main menu
+fn end_code_entry(mut commands: Commands) {
+    commands.remove_resource::<CodeEntry>();
+}
+
+/// Leaves the board of the code and restores the board options
+fn end_code(
+    mut commands: Commands,
+    run: Option<Res<CodeRun>>,
+    mut board_options: ResMut<BoardOptions>,
+) {
+    if let Some(run) = run {
+        *board_options = run.previous_options.clone();
+        commands.remove_resource::<CodeRun>();
+    }
+}
@@ -7,6 +7,66 @@ pub enum ButtonAction {
     Clear,
     Generate,
     Pause,
+    Resume,
+    Restart,
+    Quit,
+    Play,
+    Settings,
+    Menu,
+    /// Cycles the board size presets
+    BoardSize,
+    /// Cycles the bomb counter palettes
+    Palette,
+    /// Toggles the bomb counter markers
+    Markers,
+    /// Switches between the classic and flags modes
+    GameMode,
+    /// Switches between the light and dark themes
+    Theme,
+    /// Toggles the question marks
+    QuestionMarks,
+    /// Toggles the safe start
+    SafeStart,
+    /// Switches between uncovering on press and on release
+    TriggerMode,
+    /// Cycles the window sizes
+    WindowSize,
+    /// Cycles the sound volume
+    Volume,
+    /// Starts the tutorial
+    Tutorial,
+    /// Moves on to the next tutorial lesson
+    NextLesson,
+    /// Starts the daily challenge
+    Daily,
+    /// Copies the code of the current board
+    CopyCode,
+    /// Plays the board code in the clipboard
+    PlayCode,
+    /// Opens the board editor
+    Editor,
+    /// Removes a column from the edited board
+    Narrower,
+    /// Adds a column to the edited board
+    Wider,
+    /// Removes a row from the edited board
+    Shorter,
+    /// Adds a row to the edited board
+    Taller,
+    /// Toggles the safe start of the edited board
+    PuzzleSafeStart,
+    /// Saves the edited board to a puzzle file
+    SavePuzzle,
+    /// Plays the edited board
+    PlayPuzzle,
+    /// Opens the puzzle pack browser
+    Puzzles,
+    /// Plays a puzzle of a pack, set by the `PackPuzzle` component of the button
+    PackPuzzle,
+    /// Starts a time attack run
+    TimeAttack,
+    /// Toggles restarting time attack runs on death
+    TimeAttackReset,
 }
 
 #[derive(Debug)]
@@ -15,3 +75,50 @@ pub struct ButtonColors {
     pub hovered: Color,
     pub pressed: Color,
 }
+
+/// Spawns a button with a centered text, returning its entity
+pub fn setup_single_menu(
+    parent: &mut ChildBuilder,
+    text: &str,
+    color: UiColor,
+    font: Handle<Font>,
+    font_size: f32,
+    action: ButtonAction,
+) -> Entity {
+    parent
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Percent(95.), Val::Auto),
+                margin: Rect::all(Val::Px(10.)),
+                // horizontally center child text
+                justify_content: JustifyContent::Center,
+                // vertically center child text
+                align_items: AlignItems::Center,
+                ..Default::default()
+            },
+            color,
+            ..Default::default()
+        })
+        .insert(action)
+        .insert(Name::new(text.to_string()))
+        .with_children(|builder| {
+            builder.spawn_bundle(TextBundle {
+                text: Text {
+                    sections: vec![TextSection {
+                        value: text.to_string(),
+                        style: TextStyle {
+                            font,
+                            font_size,
+                            color: Color::WHITE,
+                        },
+                    }],
+                    alignment: TextAlignment {
+                        vertical: VerticalAlign::Center,
+                        horizontal: HorizontalAlign::Center,
+                    },
+                },
+                ..Default::default()
+            });
+        })
+        .id()
+}
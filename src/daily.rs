@@ -0,0 +1,177 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::minesweeper_core::{DailyChallenge, DailyHistory, Date};
+use board_plugin::{Board, BoardOptions, GameMode};
+
+use crate::buttons::ButtonAction;
+use crate::screens::spawn_summary;
+use crate::settings::{load_data, save_data};
+use crate::{AppState, StateEvent};
+
+/// Daily results file, next to the settings file
+const HISTORY_FILE: &str = "daily.ron";
+
+/// Daily challenge being played. Must be used as a resource, the challenge runs while it exists
+#[derive(Debug)]
+pub struct DailyRun {
+    pub challenge: DailyChallenge,
+    /// Is the current attempt the scored attempt of the day
+    pub scored: bool,
+    /// Play time of the current attempt, in seconds
+    pub time: f32,
+    /// Did the current attempt end
+    pub finished: bool,
+    /// Board options to restore when leaving the challenge
+    pub(crate) previous_options: BoardOptions,
+}
+
+/// Current day, in UTC
+#[cfg(not(target_arch = "wasm32"))]
+fn today() -> Date {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    Date::from_unix_time(seconds as i64)
+}
+
+/// Current day, in UTC
+#[cfg(target_arch = "wasm32")]
+fn today() -> Date {
+    Date::from_unix_time((js_sys::Date::now() / 1000.) as i64)
+}
+
+/// Loads the daily results and registers the daily challenge systems
+pub fn setup_daily(app: &mut App) {
+    app.insert_resource(load_data::<DailyHistory>(HISTORY_FILE))
+        .add_system(start_daily)
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_daily))
+        .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(start_attempt))
+        .add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(daily_timer)
+                .with_system(daily_outcome),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(daily_summary))
+        .add_system_set(SystemSet::on_enter(AppState::Defeat).with_system(daily_summary));
+}
+
+/// Starts the challenge of the day
+fn start_daily(
+    mut commands: Commands,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked || *action != ButtonAction::Daily {
+            continue;
+        }
+        let challenge = DailyChallenge { date: today() };
+        log::info!("starting daily challenge {}", challenge.date);
+        let run = DailyRun {
+            challenge,
+            scored: false,
+            time: 0.,
+            finished: false,
+            previous_options: board_options.clone(),
+        };
+        let options = challenge.options();
+        board_options.map_size = options.map_size;
+        board_options.bomb_count = options.bomb_count;
+        board_options.safe_start = options.safe_start;
+        board_options.seed = options.seed;
+        board_options.game_mode = GameMode::Classic;
+        board_options.tile_map = None;
+        board_options.start = None;
+        commands.insert_resource(run);
+        state_wr.send(StateEvent(AppState::InGame));
+    }
+}
+
+/// Leaves the challenge and restores the board options
+fn end_daily(
+    mut commands: Commands,
+    run: Option<Res<DailyRun>>,
+    mut board_options: ResMut<BoardOptions>,
+) {
+    if let Some(run) = run {
+        *board_options = run.previous_options.clone();
+        commands.remove_resource::<DailyRun>();
+    }
+}
+
+/// Only the first attempt of the day is scored, the next ones are practice
+fn start_attempt(run: Option<ResMut<DailyRun>>, mut history: ResMut<DailyHistory>) {
+    let mut run = match run {
+        Some(r) => r,
+        None => return,
+    };
+    run.scored = history.start(run.challenge.date);
+    run.time = 0.;
+    run.finished = false;
+    if run.scored {
+        save_data(HISTORY_FILE, &*history);
+    } else {
+        log::info!("daily challenge already played, this attempt is not scored");
+    }
+}
+
+fn daily_timer(time: Res<Time>, run: Option<ResMut<DailyRun>>) {
+    if let Some(mut run) = run {
+        if !run.finished {
+            run.time += time.delta_seconds();
+        }
+    }
+}
+
+/// Records the outcome of the scored attempt
+fn daily_outcome(
+    run: Option<ResMut<DailyRun>>,
+    mut history: ResMut<DailyHistory>,
+    boards: Query<&Board>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    let mut run = match run {
+        Some(r) if !r.finished => r,
+        _ => return,
+    };
+    let board = board_completed_evr
+        .iter()
+        .map(|e| e.board)
+        .chain(bomb_explosion_evr.iter().map(|e| e.board))
+        .find_map(|entity| boards.get(entity).ok());
+    let board = match board {
+        Some(b) => b,
+        None => return,
+    };
+    run.finished = true;
+    if !run.scored {
+        return;
+    }
+    let date = run.challenge.date;
+    history.finish(date, board.game.status(), run.time, board.game.cleared());
+    save_data(HISTORY_FILE, &*history);
+    if let Some(text) = history.share_text(date) {
+        log::info!("{}", text);
+    }
+}
+
+/// Shows the shareable result of the day on the end screens
+fn daily_summary(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run: Option<Res<DailyRun>>,
+    history: Res<DailyHistory>,
+) {
+    let run = match run {
+        Some(r) => r,
+        None => return,
+    };
+    let mut text = history.share_text(run.challenge.date).unwrap_or_default();
+    if !run.scored {
+        text = format!("Practice: {:.1}s\n{}", run.time, text);
+    }
+    spawn_summary(&mut commands, &asset_server, "Daily Summary", text);
+}
@@ -0,0 +1,475 @@
+use bevy::log;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+use board_plugin::components::Coordinates;
+use board_plugin::minesweeper_core::{Puzzle, PuzzlePack, Tile, TileMap};
+use board_plugin::{cursor_over_ui, BoardAssets, BoardOptions, Bounds2, GameMode};
+use std::path::PathBuf;
+
+use crate::buttons::{setup_single_menu, ButtonAction, ButtonColors};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::settings::data_path;
+use crate::{AppState, StateEvent};
+
+/// Window space reserved above the board for the editor panel
+const PANEL_HEIGHT: f32 = 140.;
+/// Smallest and largest board width and height
+const SIZE_RANGE: (u16, u16) = (3, 40);
+
+/// Board being edited. Must be used as a resource, kept between editor sessions
+#[derive(Debug)]
+pub struct Editor {
+    pub tile_map: TileMap,
+    /// Does the puzzle start with its first empty tile uncovered
+    pub safe_start: bool,
+    /// Name of the puzzle file, set once saved
+    pub name: Option<String>,
+    /// Last editor message
+    message: String,
+}
+
+impl Default for Editor {
+    fn default() -> Self {
+        Self {
+            tile_map: TileMap::empty(9, 9),
+            safe_start: true,
+            name: None,
+            message: "Click tiles to place or remove mines".to_string(),
+        }
+    }
+}
+
+impl Editor {
+    /// Resizes the board, keeping the bombs that still fit
+    fn resize(&mut self, width: u16, height: u16) {
+        let (width, height) = (
+            width.clamp(SIZE_RANGE.0, SIZE_RANGE.1),
+            height.clamp(SIZE_RANGE.0, SIZE_RANGE.1),
+        );
+        let bombs: Vec<Coordinates> = self
+            .tile_map
+            .coordinates()
+            .filter(|c| self.tile_map.is_bomb_at(*c))
+            .collect();
+        self.tile_map = TileMap::with_bombs(width, height, bombs);
+    }
+
+    /// Places or removes the bomb of a tile
+    fn toggle_bomb(&mut self, coords: Coordinates) {
+        let tile = &mut self.tile_map[coords.y as usize][coords.x as usize];
+        *tile = if tile.is_bomb() {
+            Tile::Empty
+        } else {
+            Tile::Bomb
+        };
+        self.tile_map.set_bomb_neighbors();
+    }
+
+    /// Puzzle of the edited board, starting on its first empty tile with the safe start
+    fn puzzle(&self) -> Puzzle {
+        let name = self.name.clone().unwrap_or_default();
+        Puzzle {
+            start: self.tile_map.safe_start().filter(|_| self.safe_start),
+            ..Puzzle::new(name, &self.tile_map)
+        }
+    }
+}
+
+/// Editor test game. Must be used as a resource, the edited board is played while it exists
+#[derive(Debug)]
+pub struct EditorTest {
+    /// Board options to restore when leaving the test game
+    pub(crate) previous_options: BoardOptions,
+}
+
+/// Editor controls and status
+#[derive(Debug, Copy, Clone, Component)]
+struct EditorPanel;
+
+/// Editor status text
+#[derive(Debug, Copy, Clone, Component)]
+struct EditorStatus;
+
+/// Displayed board of the editor
+#[derive(Debug, Copy, Clone, Component)]
+struct EditorBoard {
+    bounds: Bounds2,
+    tile_size: f32,
+}
+
+/// Puzzle files directory, `puzzles` next to the settings file
+#[cfg(not(target_arch = "wasm32"))]
+pub fn puzzles_dir() -> Option<PathBuf> {
+    data_path("puzzles")
+}
+
+/// Writes the board as a puzzle pack of its own, named after the first free
+/// `puzzle-<n>.pack.ron` on its first save
+#[cfg(not(target_arch = "wasm32"))]
+fn save_puzzle(editor: &mut Editor) -> Result<PathBuf, String> {
+    let dir = puzzles_dir().ok_or("no config directory")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let name = editor.name.clone().unwrap_or_else(|| {
+        (1..)
+            .map(|n| format!("puzzle-{}", n))
+            .find(|name| !dir.join(format!("{}.pack.ron", name)).exists())
+            .unwrap()
+    });
+    editor.name = Some(name.clone());
+    let path = dir.join(format!("{}.pack.ron", name));
+    let pack = PuzzlePack {
+        title: name,
+        author: String::new(),
+        puzzles: vec![editor.puzzle()],
+    };
+    let content =
+        ron::ser::to_string_pretty(&pack, Default::default()).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn save_puzzle(_editor: &mut Editor) -> Result<PathBuf, String> {
+    Err("the browser build can't save files".to_string())
+}
+
+/// Registers the editor systems
+pub fn setup_editor(app: &mut App) {
+    app.init_resource::<Editor>()
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_test))
+        .add_system_set(
+            SystemSet::on_enter(AppState::Editor)
+                .with_system(end_test)
+                .with_system(spawn_panel),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Editor)
+                .with_system(editor_buttons)
+                .with_system(toggle_tiles)
+                .with_system(draw_board)
+                .with_system(editor_status),
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Editor).with_system(despawn_editor));
+}
+
+/// Leaves the test game and restores the board options
+fn end_test(
+    mut commands: Commands,
+    test: Option<Res<EditorTest>>,
+    mut board_options: ResMut<BoardOptions>,
+) {
+    if let Some(test) = test {
+        *board_options = test.previous_options.clone();
+        commands.remove_resource::<EditorTest>();
+    }
+}
+
+fn spawn_panel(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    let font = asset_server.load("fonts/pixeled.ttf");
+    let rows: [&[(&str, ButtonAction)]; 2] = [
+        &[
+            ("W-", ButtonAction::Narrower),
+            ("W+", ButtonAction::Wider),
+            ("H-", ButtonAction::Shorter),
+            ("H+", ButtonAction::Taller),
+            ("SAFE START", ButtonAction::PuzzleSafeStart),
+        ],
+        &[
+            ("SAVE", ButtonAction::SavePuzzle),
+            ("PLAY", ButtonAction::PlayPuzzle),
+            ("MENU", ButtonAction::Menu),
+        ],
+    ];
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(0.),
+                    left: Val::Px(0.),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Px(PANEL_HEIGHT)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0.15, 0.15, 0.15, 0.85).into(),
+            ..Default::default()
+        })
+        .insert(EditorPanel)
+        .insert(Name::new("Editor Panel"))
+        .with_children(|parent| {
+            // Filled by `editor_status`
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(5.)),
+                        max_size: Size::new(Val::Px(640.), Val::Undefined),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 12.,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(EditorStatus);
+            for buttons in rows {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.), Val::Auto),
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for (text, action) in buttons {
+                            setup_single_menu(
+                                parent,
+                                text,
+                                button_colors.normal.into(),
+                                font.clone(),
+                                14.,
+                                *action,
+                            );
+                        }
+                    });
+            }
+        });
+}
+
+#[allow(clippy::type_complexity)]
+fn despawn_editor(
+    mut commands: Commands,
+    entities: Query<Entity, Or<(With<EditorPanel>, With<EditorBoard>)>>,
+) {
+    for entity in entities.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Resizes, saves and plays the edited board
+fn editor_buttons(
+    mut commands: Commands,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut editor: ResMut<Editor>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let (width, height) = (editor.tile_map.width(), editor.tile_map.height());
+        match action {
+            ButtonAction::Narrower => editor.resize(width.saturating_sub(1), height),
+            ButtonAction::Wider => editor.resize(width + 1, height),
+            ButtonAction::Shorter => editor.resize(width, height.saturating_sub(1)),
+            ButtonAction::Taller => editor.resize(width, height + 1),
+            ButtonAction::PuzzleSafeStart => editor.safe_start = !editor.safe_start,
+            ButtonAction::SavePuzzle => {
+                editor.message = match save_puzzle(&mut editor) {
+                    Ok(path) => {
+                        log::info!("Saved puzzle to {}", path.display());
+                        format!("Saved to {}", path.display())
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save the puzzle: {}", e);
+                        format!("Save failed: {}", e)
+                    }
+                };
+            }
+            ButtonAction::PlayPuzzle => {
+                let puzzle = editor.puzzle();
+                let tile_map = match puzzle.tile_map() {
+                    Ok(t) => t,
+                    Err(e) => {
+                        editor.message = format!("Can't play: {}", e);
+                        continue;
+                    }
+                };
+                commands.insert_resource(EditorTest {
+                    previous_options: board_options.clone(),
+                });
+                board_options.tile_map = Some(tile_map);
+                board_options.start = puzzle.start;
+                board_options.safe_start = false;
+                board_options.game_mode = GameMode::Classic;
+                state_wr.send(StateEvent(AppState::InGame));
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Places or removes mines on the clicked tiles
+fn toggle_tiles(
+    windows: Res<Windows>,
+    mouse: Res<Input<MouseButton>>,
+    ui_nodes: Query<(&Node, &GlobalTransform)>,
+    boards: Query<&EditorBoard>,
+    mut editor: ResMut<Editor>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let window = windows.get_primary().unwrap();
+    let position = match window.cursor_position() {
+        // The cursor never reaches the board through UI nodes
+        Some(p) if !cursor_over_ui(p, ui_nodes.iter()) => {
+            p - Vec2::new(window.width(), window.height()) / 2.
+        }
+        _ => return,
+    };
+    for board in boards.iter() {
+        if !board.bounds.in_bounds(position) {
+            continue;
+        }
+        let coordinates = (position - board.bounds.position) / board.tile_size;
+        let coords = Coordinates {
+            x: coordinates.x as u16,
+            y: coordinates.y as u16,
+        };
+        if editor.tile_map.contains(coords) {
+            editor.toggle_bomb(coords);
+        }
+    }
+}
+
+/// Draws the edited board again whenever it or the window changes
+fn draw_board(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    windows: Res<Windows>,
+    board_options: Res<BoardOptions>,
+    board_assets: Res<BoardAssets>,
+    mut resized_evr: EventReader<WindowResized>,
+    boards: Query<Entity, With<EditorBoard>>,
+) {
+    let resized = resized_evr.iter().count() > 0;
+    if !editor.is_changed() && !resized && !boards.is_empty() {
+        return;
+    }
+    for board in boards.iter() {
+        commands.entity(board).despawn_recursive();
+    }
+    let window = windows.get_primary().unwrap();
+    let tile_map = &editor.tile_map;
+    // Laid out like the game board, below the editor panel
+    let mut options = board_options.clone();
+    options.margins.top = PANEL_HEIGHT;
+    let tile_size = options.tile_size_for(window, (tile_map.width(), tile_map.height()));
+    let size = tile_size - options.tile_padding;
+    let board_size = Vec2::new(
+        tile_map.width() as f32 * tile_size,
+        tile_map.height() as f32 * tile_size,
+    );
+    let board_position = options.board_position(window, board_size);
+    commands
+        .spawn()
+        .insert(Name::new("Editor Board"))
+        .insert(Transform::from_translation(board_position))
+        .insert(GlobalTransform::default())
+        .insert(EditorBoard {
+            bounds: Bounds2 {
+                position: board_position.xy(),
+                size: board_size,
+            },
+            tile_size,
+        })
+        .with_children(|parent| {
+            parent.spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(board_size),
+                    color: board_assets.board_material.color,
+                    ..Default::default()
+                },
+                texture: board_assets.board_material.texture.clone(),
+                transform: Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
+                ..Default::default()
+            });
+            for coords in tile_map.coordinates() {
+                let tile = tile_map[coords.y as usize][coords.x as usize];
+                parent
+                    .spawn_bundle(SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(size)),
+                            color: board_assets.tile_material.color,
+                            ..Default::default()
+                        },
+                        texture: board_assets.tile_material.texture.clone(),
+                        transform: Transform::from_xyz(
+                            (coords.x as f32 + 0.5) * tile_size,
+                            (coords.y as f32 + 0.5) * tile_size,
+                            1.,
+                        ),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| match tile {
+                        Tile::Bomb => {
+                            parent.spawn_bundle(SpriteBundle {
+                                sprite: Sprite {
+                                    custom_size: Some(Vec2::splat(size)),
+                                    color: board_assets.bomb_material.color,
+                                    ..Default::default()
+                                },
+                                texture: board_assets.bomb_material.texture.clone(),
+                                transform: Transform::from_xyz(0., 0., 1.),
+                                ..Default::default()
+                            });
+                        }
+                        Tile::BombNeighbor(count) => {
+                            parent.spawn_bundle(Text2dBundle {
+                                text: Text::with_section(
+                                    count.to_string(),
+                                    TextStyle {
+                                        color: board_assets.bomb_counter_color(count),
+                                        font: board_assets.bomb_counter_font.clone(),
+                                        font_size: size,
+                                    },
+                                    TextAlignment {
+                                        vertical: VerticalAlign::Center,
+                                        horizontal: HorizontalAlign::Center,
+                                    },
+                                ),
+                                transform: Transform::from_xyz(0., 0., 1.),
+                                ..Default::default()
+                            });
+                        }
+                        Tile::Empty => (),
+                    });
+            }
+        });
+}
+
+fn editor_status(editor: Res<Editor>, mut texts: Query<&mut Text, With<EditorStatus>>) {
+    for mut text in texts.iter_mut() {
+        let tile_map = &editor.tile_map;
+        let value = format!(
+            "{}x{} - {} MINES - SAFE START {}\n{}",
+            tile_map.width(),
+            tile_map.height(),
+            tile_map.bomb_count(),
+            if editor.safe_start { "ON" } else { "OFF" },
+            editor.message
+        );
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
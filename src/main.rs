@@ -1,18 +1,41 @@
+mod board_code;
 mod buttons;
+mod daily;
+mod editor;
+#[cfg(feature = "net")]
+mod net;
+mod puzzles;
+mod screens;
+mod settings;
+mod time_attack;
+mod tutorial;
 
+use bevy::app::AppExit;
 use bevy::log;
 use bevy::log::{Level, LogSettings};
 use bevy::prelude::*;
 
-use crate::buttons::{ButtonAction, ButtonColors};
+use crate::board_code::CodeEntry;
+use crate::buttons::{setup_single_menu, ButtonAction, ButtonColors};
+use crate::settings::{ModeRuns, Settings};
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::RegisterInspectable;
-use board_plugin::{BoardAssets, BoardOptions, BoardPlugin, BoardPosition, SpriteMaterial};
+use board_plugin::components::{FlagsGame, FlagsOutcome};
+use board_plugin::{
+    BoardAssets, BoardMargins, BoardOptions, BoardPlugin, Bot, BotPlugin, GameMode, SpriteMaterial,
+};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum AppState {
+    MainMenu,
+    Settings,
+    Editor,
+    Puzzles,
     InGame,
     Pause,
+    Victory,
+    Defeat,
     Out,
 }
 
@@ -21,15 +44,22 @@ pub struct StateEvent(pub AppState);
 #[derive(Debug, Copy, Clone)]
 pub struct ReloadEvent;
 
+/// Flags mode scores and turn indicator
+#[derive(Debug, Copy, Clone, Component)]
+pub struct FlagsHud;
+
 fn main() {
+    // Settings are needed before the window is created
+    let settings = Settings::load();
     let mut app = App::new();
     // Window setup
     app.insert_resource(WindowDescriptor {
         title: "Mine Sweeper!".to_string(),
-        width: 700.,
-        height: 750.,
+        width: settings.window_size.0,
+        height: settings.window_size.1,
         ..Default::default()
     })
+    .insert_resource(settings)
     // Log setup
     .insert_resource(LogSettings {
         level: Level::INFO,
@@ -43,60 +73,74 @@ fn main() {
         app.add_plugin(bevy_inspector_egui::WorldInspectorPlugin::new());
         app.register_inspectable::<ButtonAction>();
     }
+    // Versus race or co-op client
+    #[cfg(feature = "net")]
+    net::setup_net(&mut app);
     // Board plugin
-    app.add_plugin(BoardPlugin {
-        running_state: AppState::InGame,
-    })
-    .add_state(AppState::Out)
-    .add_startup_system(setup_board)
-    // Startup system (cameras)
-    .add_startup_system(setup_camera)
-    // UI
-    .add_startup_system(setup_ui)
-    // State handling
-    .add_event::<StateEvent>()
-    .add_event::<ReloadEvent>()
-    .add_system(input_handler)
-    .add_system(key_handler)
-    .add_system(state_handler)
-    .add_system(reload_handler)
+    app.add_plugin(BoardPlugin::new(AppState::InGame))
+        // Bot player, toggled with B
+        .add_plugin(BotPlugin {
+            running_state: AppState::InGame,
+            interval: Duration::from_millis(250),
+            enabled: false,
+        })
+        .add_state(AppState::MainMenu)
+        .add_startup_system(setup_board)
+        // Startup system (cameras)
+        .add_startup_system(setup_camera)
+        // UI
+        .add_startup_system(setup_ui)
+        // State handling
+        .add_event::<StateEvent>()
+        .add_event::<ReloadEvent>()
+        .add_system(input_handler)
+        .add_system(key_handler)
+        .add_system(state_handler)
+        .add_system(reload_handler)
+        .add_system(settings::apply_settings)
+        .add_system(flags_hud);
+    // Menus, pause and game over screens
+    screens::setup_screens(&mut app);
+    // Scripted lessons on hand-made boards
+    tutorial::setup_tutorial(&mut app);
+    // Seeded board of the day
+    daily::setup_daily(&mut app);
+    // Shareable board codes
+    board_code::setup_board_code(&mut app);
+    // Hand-made boards and puzzle files
+    editor::setup_editor(&mut app);
+    // Puzzle packs
+    puzzles::setup_puzzles(&mut app);
+    // Chained boards against the clock
+    time_attack::setup_time_attack(&mut app);
     // Run the app
-    .run();
+    app.run();
 }
 
-fn setup_board(
-    mut commands: Commands,
-    mut state: ResMut<State<AppState>>,
-    asset_server: Res<AssetServer>,
-) {
+fn setup_board(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<Settings>) {
     // Board plugin options
-    commands.insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 50,
+    let mut board_options = BoardOptions {
         tile_padding: 1.,
-        safe_start: true,
-        position: BoardPosition::Centered {
-            offset: Vec3::new(0., 25., 0.),
+        // We leave room for the UI bar
+        margins: BoardMargins {
+            bottom: 50.,
+            ..Default::default()
         },
         ..Default::default()
-    });
+    };
+    settings.apply_to_options(&mut board_options);
+    commands.insert_resource(board_options);
     // Board assets
-    commands.insert_resource(BoardAssets {
+    let mut board_assets = BoardAssets {
         label: "Default".to_string(),
-        board_material: SpriteMaterial {
-            color: Color::WHITE,
-            ..Default::default()
-        },
-        tile_material: SpriteMaterial {
-            color: Color::DARK_GRAY,
-            ..Default::default()
-        },
-        covered_tile_material: SpriteMaterial {
-            color: Color::GRAY,
-            ..Default::default()
-        },
+        // Colors are set by the settings theme
+        board_material: Default::default(),
+        tile_material: Default::default(),
+        covered_tile_material: Default::default(),
+        pressed_tile_material: Default::default(),
         bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
-        bomb_counter_colors: BoardAssets::default_colors(),
+        bomb_counter_colors: Default::default(),
+        counter_markers: false,
         flag_material: SpriteMaterial {
             texture: asset_server.load("sprites/flag.png"),
             color: Color::WHITE,
@@ -105,9 +149,10 @@ fn setup_board(
             texture: asset_server.load("sprites/bomb.png"),
             color: Color::WHITE,
         },
-    });
-    // Launch game
-    state.set(AppState::InGame).unwrap();
+        player_colors: BoardAssets::default_player_colors(),
+    };
+    settings.apply_to_assets(&mut board_assets);
+    commands.insert_resource(board_assets);
 }
 
 fn setup_camera(mut commands: Commands) {
@@ -125,6 +170,7 @@ fn input_handler(
         (Changed<Interaction>, With<Button>),
     >,
     mut state_wr: EventWriter<StateEvent>,
+    mut app_exit_wr: EventWriter<AppExit>,
 ) {
     for (interaction, action, mut color) in interaction_query.iter_mut() {
         match *interaction {
@@ -139,10 +185,51 @@ fn input_handler(
                         log::debug!("loading detected");
                         state_wr.send(StateEvent(AppState::InGame))
                     }
-                    ButtonAction::Pause => {
+                    ButtonAction::Pause | ButtonAction::Resume => {
                         log::debug!("pausing detected");
                         state_wr.send(StateEvent(AppState::Pause))
                     }
+                    ButtonAction::Restart => {
+                        log::debug!("restart detected");
+                        state_wr.send(StateEvent(AppState::InGame))
+                    }
+                    ButtonAction::Quit => {
+                        log::debug!("quit detected");
+                        app_exit_wr.send(AppExit)
+                    }
+                    ButtonAction::Play => state_wr.send(StateEvent(AppState::InGame)),
+                    ButtonAction::Settings => state_wr.send(StateEvent(AppState::Settings)),
+                    ButtonAction::Editor => state_wr.send(StateEvent(AppState::Editor)),
+                    ButtonAction::Puzzles => state_wr.send(StateEvent(AppState::Puzzles)),
+                    ButtonAction::Menu => state_wr.send(StateEvent(AppState::MainMenu)),
+                    // Settings are applied by the settings screen
+                    ButtonAction::BoardSize
+                    | ButtonAction::Palette
+                    | ButtonAction::Markers
+                    | ButtonAction::GameMode
+                    | ButtonAction::Theme
+                    | ButtonAction::QuestionMarks
+                    | ButtonAction::SafeStart
+                    | ButtonAction::TriggerMode
+                    | ButtonAction::WindowSize
+                    | ButtonAction::Volume
+                    | ButtonAction::TimeAttackReset => (),
+                    // Tutorial, daily challenge, board code and editor buttons are handled by their
+                    // modules
+                    ButtonAction::Tutorial
+                    | ButtonAction::NextLesson
+                    | ButtonAction::Daily
+                    | ButtonAction::CopyCode
+                    | ButtonAction::PlayCode
+                    | ButtonAction::Narrower
+                    | ButtonAction::Wider
+                    | ButtonAction::Shorter
+                    | ButtonAction::Taller
+                    | ButtonAction::PuzzleSafeStart
+                    | ButtonAction::SavePuzzle
+                    | ButtonAction::PlayPuzzle
+                    | ButtonAction::PackPuzzle
+                    | ButtonAction::TimeAttack => (),
                 }
             }
             Interaction::Hovered => {
@@ -172,6 +259,8 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             color: Color::WHITE.into(),
             ..Default::default()
         })
+        // Tracking interaction keeps the clicks on the bar from reaching the board
+        .insert(Interaction::default())
         .insert(Name::new("UI"))
         .with_children(|parent| {
             let font = asset_server.load("fonts/pixeled.ttf");
@@ -180,6 +269,7 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 "CLEAR",
                 button_materials.normal.into(),
                 font.clone(),
+                30.,
                 ButtonAction::Clear,
             );
             setup_single_menu(
@@ -187,65 +277,68 @@ fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 "GENERATE",
                 button_materials.normal.into(),
                 font.clone(),
+                30.,
                 ButtonAction::Generate,
             );
             setup_single_menu(
                 parent,
                 "PAUSE",
                 button_materials.normal.into(),
-                font,
+                font.clone(),
+                30.,
                 ButtonAction::Pause,
             );
-        });
-    commands.insert_resource(button_materials);
-}
-
-fn setup_single_menu(
-    parent: &mut ChildBuilder,
-    text: &str,
-    color: UiColor,
-    font: Handle<Font>,
-    action: ButtonAction,
-) {
-    parent
-        .spawn_bundle(ButtonBundle {
-            style: Style {
-                size: Size::new(Val::Percent(95.), Val::Auto),
-                margin: Rect::all(Val::Px(10.)),
-                // horizontally center child text
-                justify_content: JustifyContent::Center,
-                // vertically center child text
-                align_items: AlignItems::Center,
-                ..Default::default()
-            },
-            color,
-            ..Default::default()
-        })
-        .insert(action)
-        .insert(Name::new(text.to_string()))
-        .with_children(|builder| {
-            builder.spawn_bundle(TextBundle {
-                text: Text {
-                    sections: vec![TextSection {
-                        value: text.to_string(),
-                        style: TextStyle {
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(10.)),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
                             font,
-                            font_size: 30.,
-                            color: Color::WHITE,
+                            font_size: 20.,
+                            color: Color::BLACK,
                         },
-                    }],
-                    alignment: TextAlignment {
-                        vertical: VerticalAlign::Center,
-                        horizontal: HorizontalAlign::Center,
-                    },
-                },
-                ..Default::default()
-            });
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(FlagsHud)
+                .insert(Name::new("Flags HUD"));
         });
+    commands.insert_resource(button_materials);
 }
 
-fn key_handler(keys: Res<Input<KeyCode>>, mut state_wr: EventWriter<StateEvent>) {
-    if keys.just_pressed(KeyCode::C) {
+fn key_handler(
+    keys: Res<Input<KeyCode>>,
+    code_entry: Option<Res<CodeEntry>>,
+    runs: ModeRuns,
+    mut board_options: ResMut<BoardOptions>,
+    mut bot: ResMut<Bot>,
+    mut settings: ResMut<Settings>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    // The keys type the board code
+    if code_entry.is_some() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::F) {
+        // Modes playing their own boards keep their rules
+        if runs.is_running() {
+            log::info!("game mode can't change during a run");
+            return;
+        }
+        settings.game_mode = match settings.game_mode {
+            GameMode::Classic => GameMode::Flags { players: 2 },
+            GameMode::Flags { .. } => GameMode::Classic,
+        };
+        // The new game may start before the settings are applied
+        board_options.game_mode = settings.game_mode;
+        log::info!("switching to {:?} mode", board_options.game_mode);
+        state_wr.send(StateEvent(AppState::InGame))
+    } else if keys.just_pressed(KeyCode::C) {
         log::debug!("clearing detected");
         state_wr.send(StateEvent(AppState::Out))
     } else if keys.just_pressed(KeyCode::Escape) {
@@ -254,6 +347,14 @@ fn key_handler(keys: Res<Input<KeyCode>>, mut state_wr: EventWriter<StateEvent>)
     } else if keys.just_pressed(KeyCode::G) {
         log::debug!("loading detected");
         state_wr.send(StateEvent(AppState::InGame))
+    } else if keys.just_pressed(KeyCode::B) {
+        bot.enabled = !bot.enabled;
+        log::info!("bot {}", if bot.enabled { "enabled" } else { "disabled" });
+    } else if keys.just_pressed(KeyCode::V) {
+        settings.palette = settings.palette.next();
+        log::info!("switching to {:?} palette", settings.palette);
+    } else if keys.just_pressed(KeyCode::M) {
+        settings.counter_markers = !settings.counter_markers;
     }
 }
 
@@ -267,58 +368,71 @@ pub fn reload_handler(
     }
 }
 
+/// Applies the requested state changes.
+///
+/// Pause, Victory and Defeat are pushed on top of the game, every other state replaces the whole stack
 pub fn state_handler(
     mut state: ResMut<State<AppState>>,
     mut state_evr: EventReader<StateEvent>,
     mut reload_wr: EventWriter<ReloadEvent>,
 ) {
-    for state_event in state_evr.iter() {
-        match state_event.0 {
-            AppState::InGame => {
-                log::debug!("loading game");
-                match state.current() {
-                    AppState::InGame => {
-                        log::info!("generate new game when in game");
-                        state.set(AppState::Out).unwrap();
-                        reload_wr.send(ReloadEvent);
-                    }
-                    AppState::Pause => {
-                        log::info!("generate game when pausing");
-                        state.overwrite_replace(AppState::InGame).unwrap();
-                    }
-                    AppState::Out => {
-                        state.set(AppState::InGame).unwrap();
-                        log::info!("loading game");
-                    }
-                }
+    for StateEvent(target) in state_evr.iter() {
+        let current = *state.current();
+        let in_game = current == AppState::InGame || state.inactives().contains(&AppState::InGame);
+        let result = match (*target, current) {
+            // A new game is generated by clearing the current one first
+            (AppState::InGame, AppState::InGame) => {
+                reload_wr.send(ReloadEvent);
+                state.set(AppState::Out)
             }
-            AppState::Out => {
-                log::debug!("clearing game");
-                match state.current() {
-                    AppState::InGame => {
-                        log::info!("clearing game");
-                        state.set(AppState::Out).unwrap();
-                    }
-                    AppState::Pause => {
-                        log::info!("clearing game when pausing");
-                        state.overwrite_replace(AppState::Out).unwrap();
-                    }
-                    _ => {}
-                }
+            (AppState::Pause, AppState::Pause) => state.pop(),
+            (AppState::Pause | AppState::Victory | AppState::Defeat, AppState::InGame) => {
+                state.push(*target)
             }
-            AppState::Pause => {
-                log::debug!("pause or resume game");
-                match state.current() {
-                    AppState::InGame => {
-                        log::info!("pausing game");
-                        state.push(AppState::Pause).unwrap();
-                    }
-                    AppState::Pause => {
-                        log::info!("resuming game");
-                        state.pop().unwrap();
-                    }
-                    _ => {}
-                }
+            // Overlays only exist on top of a game, and there is no game to clear
+            (AppState::Pause | AppState::Victory | AppState::Defeat, _) => continue,
+            (AppState::Out, _) if !in_game => continue,
+            (target, _) => state.overwrite_replace(target),
+        };
+        match result {
+            Ok(()) => log::info!("{:?} -> {:?}", current, target),
+            Err(e) => log::debug!("Ignored {:?} -> {:?}: {:?}", current, target, e),
+        }
+    }
+}
+
+/// Shows the flags mode scores and whose turn it is
+fn flags_hud(
+    games: Query<&FlagsGame>,
+    board_assets: Option<Res<BoardAssets>>,
+    mut hud: Query<&mut Text, With<FlagsHud>>,
+) {
+    let board_assets = match board_assets {
+        Some(a) => a,
+        None => return,
+    };
+    for mut text in hud.iter_mut() {
+        let section = &mut text.sections[0];
+        match games.iter().next() {
+            None => section.value.clear(),
+            Some(game) => {
+                let scores: Vec<String> = game
+                    .scores
+                    .iter()
+                    .enumerate()
+                    .map(|(player, score)| format!("P{} {}", player + 1, score))
+                    .collect();
+                let (status, player) = match game.outcome {
+                    None => ("turn", Some(game.current_player)),
+                    Some(FlagsOutcome::Winner(p)) => ("wins", Some(p)),
+                    Some(FlagsOutcome::Draw) => ("draw", None),
+                };
+                section.value = match player {
+                    Some(p) => format!("{} - P{} {}", scores.join(" "), p + 1, status),
+                    None => format!("{} - {}", scores.join(" "), status),
+                };
+                section.style.color =
+                    player.map_or(Color::BLACK, |p| board_assets.player_color(p.into()));
             }
         }
     }
@@ -0,0 +1,132 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::net::{
+    CoopClientPlugin, NetBoardEvent, RaceClientPlugin, RaceStandings, SpectatorClientPlugin,
+    SpectatorPublisherPlugin,
+};
+use board_plugin::BoardOptions;
+
+use crate::screens::EndScreens;
+use crate::{AppState, StateEvent};
+
+/// Environment variable holding the race server address
+pub const RACE_SERVER_VAR: &str = "MINESWEEPER_RACE_SERVER";
+/// Environment variable holding the co-op server address
+pub const COOP_SERVER_VAR: &str = "MINESWEEPER_COOP_SERVER";
+/// Environment variable holding the address of the spectator stream to watch
+pub const SPECTATE_VAR: &str = "MINESWEEPER_SPECTATE";
+/// Environment variable holding the address to publish the game on for spectators
+pub const PUBLISH_VAR: &str = "MINESWEEPER_PUBLISH";
+/// Environment variable holding the player name
+pub const PLAYER_NAME_VAR: &str = "MINESWEEPER_NAME";
+
+/// Race standings text
+#[derive(Debug, Copy, Clone, Component)]
+pub struct RaceHud;
+
+/// Joins a versus race, a co-op game or a spectator stream if an address is configured, and
+/// publishes the game for spectators if requested
+pub fn setup_net(app: &mut App) {
+    if let Ok(address) = std::env::var(PUBLISH_VAR) {
+        app.add_plugin(SpectatorPublisherPlugin { address });
+    }
+    let name = std::env::var(PLAYER_NAME_VAR).unwrap_or_else(|_| "Player".to_string());
+    if let Ok(address) = std::env::var(RACE_SERVER_VAR) {
+        // Racers keep playing after an explosion
+        app.add_plugin(RaceClientPlugin { address, name })
+            .insert_resource(EndScreens {
+                victory: true,
+                defeat: false,
+            })
+            .add_startup_system(setup_race_hud)
+            .add_system(net_board_handler)
+            .add_system(race_hud);
+    } else if let Ok(address) = std::env::var(COOP_SERVER_VAR) {
+        // The shared board keeps going after an explosion
+        app.add_plugin(CoopClientPlugin { address, name })
+            .insert_resource(EndScreens {
+                victory: true,
+                defeat: false,
+            })
+            .add_system(net_board_handler);
+    } else if let Ok(address) = std::env::var(SPECTATE_VAR) {
+        app.add_plugin(SpectatorClientPlugin { address })
+            .insert_resource(EndScreens {
+                victory: false,
+                defeat: false,
+            })
+            .add_system(net_board_handler);
+    }
+}
+
+fn setup_race_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(5.),
+                    left: Val::Px(5.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            text: Text::with_section(
+                "Waiting for players",
+                TextStyle {
+                    font: asset_server.load("fonts/pixeled.ttf"),
+                    font_size: 12.,
+                    color: Color::WHITE,
+                },
+                Default::default(),
+            ),
+            ..Default::default()
+        })
+        .insert(RaceHud)
+        .insert(Name::new("Race HUD"));
+}
+
+/// Generates the board provided by the server
+fn net_board_handler(
+    mut net_board_evr: EventReader<NetBoardEvent>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for NetBoardEvent(options) in net_board_evr.iter() {
+        log::info!("network board received");
+        // We keep our own layout and only take the board rules
+        board_options.map_size = options.map_size;
+        board_options.bomb_count = options.bomb_count;
+        board_options.safe_start = options.safe_start;
+        board_options.seed = options.seed;
+        board_options.game_mode = options.game_mode;
+        state_wr.send(StateEvent(AppState::InGame));
+    }
+}
+
+fn race_hud(standings: Res<RaceStandings>, mut hud: Query<&mut Text, With<RaceHud>>) {
+    if !standings.is_changed() || standings.progress.is_empty() {
+        return;
+    }
+    let lines: Vec<String> = standings
+        .progress
+        .iter()
+        .map(|p| {
+            let marker = if standings.player == Some(p.player) {
+                ">"
+            } else {
+                " "
+            };
+            let finish = p
+                .finish_time
+                .map_or_else(String::new, |t| format!(" {:.1}s", t));
+            format!(
+                "{}{} {:.0}% x{}{}",
+                marker, p.name, p.cleared, p.explosions, finish
+            )
+        })
+        .collect();
+    for mut text in hud.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
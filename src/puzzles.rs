@@ -0,0 +1,488 @@
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::log;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::minesweeper_core::{Puzzle, PuzzlePack, PuzzleProgress};
+use board_plugin::{BoardOptions, GameMode};
+
+use crate::buttons::{setup_single_menu, ButtonAction, ButtonColors};
+use crate::screens::{spawn_summary, Screen};
+use crate::settings::{load_data, save_data};
+use crate::{AppState, StateEvent};
+
+/// Puzzle packs shipped in the assets directory
+const BUNDLED_PACKS: [&str; 1] = ["puzzles/starter.pack.ron"];
+/// Puzzle results file, next to the settings file
+const PROGRESS_FILE: &str = "puzzles.ron";
+/// Puzzle buttons on every row of the pack browser
+const ROW_LENGTH: usize = 3;
+
+/// Puzzle pack asset, loaded from `.pack.ron` files
+#[derive(Debug, TypeUuid)]
+#[uuid = "5b0f6c1e-3d2a-4e8b-9c47-1a6d2f8e0b93"]
+pub struct PuzzlePackAsset(pub PuzzlePack);
+
+/// Loads the `.pack.ron` puzzle pack files
+#[derive(Debug, Default)]
+pub struct PuzzlePackLoader;
+
+impl AssetLoader for PuzzlePackLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let pack: PuzzlePack = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(PuzzlePackAsset(pack)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["pack.ron"]
+    }
+}
+
+/// Packs listed by the pack browser. Must be used as a resource
+#[derive(Debug, Default)]
+pub struct PuzzlePacks(pub Vec<Handle<PuzzlePackAsset>>);
+
+/// Puzzle played by a pack browser button
+#[derive(Debug, Clone, Component)]
+pub struct PackPuzzle {
+    pub pack: Handle<PuzzlePackAsset>,
+    pub index: usize,
+}
+
+/// Puzzle being played. Must be used as a resource, the puzzle is played while it exists
+#[derive(Debug)]
+pub struct PuzzleRun {
+    /// Title of the pack of the puzzle
+    pub pack: String,
+    pub puzzle: Puzzle,
+    /// Play time of the current game, in seconds
+    pub time: f32,
+    /// Did the current game end
+    pub finished: bool,
+    /// Did the current game end on a bomb
+    pub lost: bool,
+    /// Is the current game the best solve of the puzzle
+    pub best: bool,
+    /// Board options to restore when leaving the puzzle
+    pub(crate) previous_options: BoardOptions,
+}
+
+/// Pack list of the browser screen
+#[derive(Debug, Copy, Clone, Component)]
+struct PackList;
+
+/// Entry of the pack list, rebuilt when the packs or the progress change
+#[derive(Debug, Copy, Clone, Component)]
+struct PackEntry;
+
+/// Reads the packs saved by the board editor
+#[cfg(not(target_arch = "wasm32"))]
+fn user_packs() -> Vec<PuzzlePack> {
+    let dir = match crate::editor::puzzles_dir() {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+    let mut paths: Vec<_> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.to_string_lossy().ends_with(".pack.ron"))
+            .collect(),
+        // Nothing saved yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(e) => {
+            log::error!("Failed to read {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let pack = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| ron::from_str(&content).map_err(|e| e.to_string()));
+            match pack {
+                Ok(p) => Some(p),
+                Err(e) => {
+                    log::error!("Invalid puzzle pack {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Browsers have no puzzles directory, only the bundled packs are listed
+#[cfg(target_arch = "wasm32")]
+fn user_packs() -> Vec<PuzzlePack> {
+    Vec::new()
+}
+
+/// Loads the puzzle results and registers the puzzle pack asset and systems
+pub fn setup_puzzles(app: &mut App) {
+    app.add_asset::<PuzzlePackAsset>()
+        .init_asset_loader::<PuzzlePackLoader>()
+        .insert_resource(load_data::<PuzzleProgress>(PROGRESS_FILE))
+        .init_resource::<PuzzlePacks>()
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_puzzle))
+        .add_system_set(
+            SystemSet::on_enter(AppState::Puzzles)
+                .with_system(end_puzzle)
+                .with_system(load_packs)
+                .with_system(spawn_browser),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Puzzles)
+                .with_system(pack_list)
+                .with_system(play_puzzle),
+        )
+        .add_system_set(
+            SystemSet::on_exit(AppState::Puzzles).with_system(crate::screens::despawn_screens),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(start_attempt))
+        .add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(puzzle_timer)
+                .with_system(puzzle_outcome),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(puzzle_summary))
+        .add_system_set(SystemSet::on_enter(AppState::Defeat).with_system(puzzle_summary));
+}
+
+/// Lists the bundled packs and reads the saved packs again, they may have been edited
+fn load_packs(
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<Assets<PuzzlePackAsset>>,
+    mut packs: ResMut<PuzzlePacks>,
+) {
+    packs.0 = BUNDLED_PACKS
+        .iter()
+        .map(|path| asset_server.load(*path))
+        .collect();
+    for pack in user_packs() {
+        packs.0.push(assets.add(PuzzlePackAsset(pack)));
+    }
+}
+
+fn spawn_browser(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    let font = asset_server.load("fonts/pixeled.ttf");
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: Color::rgb(0.15, 0.15, 0.15).into(),
+            ..Default::default()
+        })
+        .insert(Screen)
+        .insert(Name::new("Puzzles Screen"))
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect::all(Val::Px(10.)),
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    "PUZZLES",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 50.,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+            // Filled by `pack_list`
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(600.), Val::Auto),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::NONE.into(),
+                    ..Default::default()
+                })
+                .insert(PackList);
+            setup_single_menu(
+                parent,
+                "MENU",
+                button_colors.normal.into(),
+                font,
+                30.,
+                ButtonAction::Menu,
+            );
+        });
+}
+
+/// Label of a puzzle button, with the best time and the target time
+fn puzzle_label(index: usize, puzzle: &Puzzle, progress: &PuzzleProgress, pack: &str) -> String {
+    let best = progress
+        .record(pack, &puzzle.title)
+        .and_then(|r| r.best_time)
+        .map_or("-".to_string(), |t| format!("{:.1}S", t));
+    let target = puzzle
+        .target_time
+        .map_or(String::new(), |t| format!(" / {:.0}S", t));
+    format!(
+        "{}. {}\n{}{}",
+        index + 1,
+        puzzle.title.to_uppercase(),
+        best,
+        target
+    )
+}
+
+/// Lists every loaded pack with the player progress
+#[allow(clippy::too_many_arguments)]
+fn pack_list(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+    packs: Res<PuzzlePacks>,
+    assets: Res<Assets<PuzzlePackAsset>>,
+    progress: Res<PuzzleProgress>,
+    mut asset_evr: EventReader<AssetEvent<PuzzlePackAsset>>,
+    lists: Query<Entity, With<PackList>>,
+    new_lists: Query<(), Added<PackList>>,
+    entries: Query<Entity, With<PackEntry>>,
+) {
+    let assets_changed = asset_evr.iter().count() > 0;
+    if new_lists.is_empty() && !assets_changed && !progress.is_changed() {
+        return;
+    }
+    let list = match lists.iter().next() {
+        Some(l) => l,
+        None => return,
+    };
+    for entry in entries.iter() {
+        commands.entity(entry).despawn_recursive();
+    }
+    let font = asset_server.load("fonts/pixeled.ttf");
+    let mut buttons = Vec::new();
+    commands.entity(list).with_children(|parent| {
+        for handle in packs.0.iter() {
+            let pack = match assets.get(handle) {
+                Some(p) => &p.0,
+                // Still loading, or failed to load
+                None => continue,
+            };
+            let mut title = format!(
+                "{} - {}/{} SOLVED",
+                pack.title.to_uppercase(),
+                progress.solved_count(pack),
+                pack.puzzles.len()
+            );
+            if !pack.author.is_empty() {
+                title = format!("{}\nBY {}", title, pack.author.to_uppercase());
+            }
+            parent
+                .spawn_bundle(TextBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(5.)),
+                        ..Default::default()
+                    },
+                    text: Text::with_section(
+                        title,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 16.,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(PackEntry);
+            for (row, puzzles) in pack.puzzles.chunks(ROW_LENGTH).enumerate() {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Percent(100.), Val::Auto),
+                            justify_content: JustifyContent::Center,
+                            ..Default::default()
+                        },
+                        color: Color::NONE.into(),
+                        ..Default::default()
+                    })
+                    .insert(PackEntry)
+                    .with_children(|parent| {
+                        for (i, puzzle) in puzzles.iter().enumerate() {
+                            let index = row * ROW_LENGTH + i;
+                            let button = setup_single_menu(
+                                parent,
+                                &puzzle_label(index, puzzle, &progress, &pack.title),
+                                button_colors.normal.into(),
+                                font.clone(),
+                                12.,
+                                ButtonAction::PackPuzzle,
+                            );
+                            buttons.push((
+                                button,
+                                PackPuzzle {
+                                    pack: handle.clone(),
+                                    index,
+                                },
+                            ));
+                        }
+                    });
+            }
+        }
+    });
+    for (button, puzzle) in buttons {
+        commands.entity(button).insert(puzzle);
+    }
+}
+
+/// Plays the puzzle of the clicked button
+fn play_puzzle(
+    mut commands: Commands,
+    interactions: Query<(&Interaction, &PackPuzzle), Changed<Interaction>>,
+    assets: Res<Assets<PuzzlePackAsset>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for (interaction, button) in interactions.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let pack = match assets.get(&button.pack) {
+            Some(p) => &p.0,
+            None => continue,
+        };
+        let puzzle = &pack.puzzles[button.index];
+        let tile_map = match puzzle.tile_map() {
+            Ok(t) => t,
+            Err(e) => {
+                log::error!("Invalid puzzle {} of {}: {}", puzzle.title, pack.title, e);
+                continue;
+            }
+        };
+        log::info!("playing puzzle {} of {}", puzzle.title, pack.title);
+        commands.insert_resource(PuzzleRun {
+            pack: pack.title.clone(),
+            puzzle: puzzle.clone(),
+            time: 0.,
+            finished: false,
+            lost: false,
+            best: false,
+            previous_options: board_options.clone(),
+        });
+        board_options.tile_map = Some(tile_map);
+        board_options.start = puzzle.start;
+        board_options.safe_start = false;
+        board_options.game_mode = GameMode::Classic;
+        state_wr.send(StateEvent(AppState::InGame));
+    }
+}
+
+/// Leaves the puzzle and restores the board options
+fn end_puzzle(
+    mut commands: Commands,
+    run: Option<Res<PuzzleRun>>,
+    mut board_options: ResMut<BoardOptions>,
+) {
+    if let Some(run) = run {
+        *board_options = run.previous_options.clone();
+        commands.remove_resource::<PuzzleRun>();
+    }
+}
+
+/// Counts every started game of the puzzle
+fn start_attempt(run: Option<ResMut<PuzzleRun>>, mut progress: ResMut<PuzzleProgress>) {
+    let mut run = match run {
+        Some(r) => r,
+        None => return,
+    };
+    run.time = 0.;
+    run.finished = false;
+    run.lost = false;
+    run.best = false;
+    progress.start(&run.pack, &run.puzzle.title);
+    save_data(PROGRESS_FILE, &*progress);
+}
+
+fn puzzle_timer(time: Res<Time>, run: Option<ResMut<PuzzleRun>>) {
+    if let Some(mut run) = run {
+        if !run.finished {
+            run.time += time.delta_seconds();
+        }
+    }
+}
+
+/// Records the solving time of the puzzle
+fn puzzle_outcome(
+    run: Option<ResMut<PuzzleRun>>,
+    mut progress: ResMut<PuzzleProgress>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    let mut run = match run {
+        Some(r) if !r.finished => r,
+        _ => return,
+    };
+    if bomb_explosion_evr.iter().count() > 0 {
+        run.finished = true;
+        run.lost = true;
+    } else if board_completed_evr.iter().count() > 0 {
+        run.finished = true;
+        let time = run.time;
+        run.best = progress.solve(&run.pack, &run.puzzle.title, time);
+        save_data(PROGRESS_FILE, &*progress);
+    }
+}
+
+/// Shows the solving time against the target and best times on the end screens
+fn puzzle_summary(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run: Option<Res<PuzzleRun>>,
+    progress: Res<PuzzleProgress>,
+) {
+    let run = match run {
+        Some(r) => r,
+        None => return,
+    };
+    let puzzle = &run.puzzle;
+    let mut text = format!("{} ({})\n", puzzle.title, run.pack);
+    if run.lost {
+        text += &format!("Lost at {:.1}s", run.time);
+    } else if run.best {
+        text += &format!("Solved in {:.1}s, new best", run.time);
+    } else {
+        text += &format!("Solved in {:.1}s", run.time);
+        let best = progress
+            .record(&run.pack, &puzzle.title)
+            .and_then(|r| r.best_time);
+        if let Some(best) = best {
+            text += &format!(" - best {:.1}s", best);
+        }
+    }
+    if let Some(target) = puzzle.target_time {
+        let beaten = run.finished && !run.lost && run.time <= target;
+        text += &format!(
+            " - target {:.0}s{}",
+            target,
+            if beaten { " beaten" } else { "" }
+        );
+    }
+    spawn_summary(&mut commands, &asset_server, "Puzzle Summary", text);
+}
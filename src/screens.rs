@@ -0,0 +1,439 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::components::{FlagsGame, FlagsOutcome};
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::minesweeper_core::GamePreset;
+use board_plugin::{GameMode, TriggerMode};
+
+use crate::buttons::{setup_single_menu, ButtonAction, ButtonColors};
+use crate::settings::{Settings, WINDOW_SIZES};
+use crate::time_attack::TimeAttackRun;
+use crate::tutorial::Tutorial;
+use crate::{AppState, StateEvent};
+
+/// Root node of a state screen
+#[derive(Debug, Copy, Clone, Component)]
+pub struct Screen;
+
+/// Which board outcomes end the game with a screen. Must be used as a resource.
+///
+/// Network games keep going after an explosion, and spectators never end the game
+#[derive(Debug, Copy, Clone)]
+pub struct EndScreens {
+    pub victory: bool,
+    pub defeat: bool,
+}
+
+impl Default for EndScreens {
+    fn default() -> Self {
+        Self {
+            victory: true,
+            defeat: true,
+        }
+    }
+}
+
+/// Registers the screen of every menu state
+pub fn setup_screens(app: &mut App) {
+    app.init_resource::<EndScreens>()
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(spawn_main_menu))
+        .add_system_set(SystemSet::on_exit(AppState::MainMenu).with_system(despawn_screens))
+        .add_system_set(SystemSet::on_enter(AppState::Settings).with_system(spawn_settings))
+        .add_system_set(
+            SystemSet::on_update(AppState::Settings)
+                .with_system(settings_handler)
+                .with_system(settings_labels),
+        )
+        .add_system_set(SystemSet::on_exit(AppState::Settings).with_system(despawn_screens))
+        .add_system_set(SystemSet::on_enter(AppState::Pause).with_system(spawn_pause))
+        .add_system_set(SystemSet::on_exit(AppState::Pause).with_system(despawn_screens))
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(spawn_victory))
+        .add_system_set(SystemSet::on_exit(AppState::Victory).with_system(despawn_screens))
+        .add_system_set(SystemSet::on_enter(AppState::Defeat).with_system(spawn_defeat))
+        .add_system_set(SystemSet::on_exit(AppState::Defeat).with_system(despawn_screens))
+        .add_system_set(SystemSet::on_update(AppState::InGame).with_system(game_over_handler));
+}
+
+/// Spawns a screen with a title and a column of buttons with the given font size.
+///
+/// Opaque screens hide the board, the others leave it visible around their panel
+fn spawn_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    button_colors: &ButtonColors,
+    title: &str,
+    opaque: bool,
+    font_size: f32,
+    buttons: &[(&str, ButtonAction)],
+) {
+    let font = asset_server.load("fonts/pixeled.ttf");
+    let (background, panel) = if opaque {
+        (Color::rgb(0.15, 0.15, 0.15), Color::NONE)
+    } else {
+        (Color::NONE, Color::rgba(0.15, 0.15, 0.15, 0.85))
+    };
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.), Val::Percent(100.)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: background.into(),
+            ..Default::default()
+        })
+        // Tracking interaction keeps the clicks on the screen from reaching the board
+        .insert(Interaction::default())
+        .insert(Screen)
+        .insert(Name::new(format!("{} Screen", title)))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(500.), Val::Auto),
+                        flex_direction: FlexDirection::ColumnReverse,
+                        align_items: AlignItems::Center,
+                        padding: Rect::all(Val::Px(10.)),
+                        ..Default::default()
+                    },
+                    color: panel.into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        style: Style {
+                            margin: Rect::all(Val::Px(10.)),
+                            ..Default::default()
+                        },
+                        text: Text::with_section(
+                            title,
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 50.,
+                                color: Color::WHITE,
+                            },
+                            Default::default(),
+                        ),
+                        ..Default::default()
+                    });
+                    for (text, action) in buttons {
+                        setup_single_menu(
+                            parent,
+                            text,
+                            button_colors.normal.into(),
+                            font.clone(),
+                            font_size,
+                            *action,
+                        );
+                    }
+                });
+        });
+}
+
+/// Spawns a text panel in the top left corner of the current screen, removed with it
+pub fn spawn_summary(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    name: &str,
+    text: String,
+) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(10.),
+                    left: Val::Px(10.),
+                    ..Default::default()
+                },
+                padding: Rect::all(Val::Px(5.)),
+                ..Default::default()
+            },
+            color: Color::rgba(0.15, 0.15, 0.15, 0.85).into(),
+            ..Default::default()
+        })
+        .insert(Screen)
+        .insert(Name::new(name.to_string()))
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    max_size: Size::new(Val::Px(640.), Val::Undefined),
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    text,
+                    TextStyle {
+                        font: asset_server.load("fonts/pixeled.ttf"),
+                        font_size: 12.,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+        });
+}
+
+pub(crate) fn despawn_screens(mut commands: Commands, screens: Query<Entity, With<Screen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+fn spawn_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        &button_colors,
+        "MINESWEEPER",
+        true,
+        // Smaller buttons so every mode fits the smallest window
+        24.,
+        &[
+            ("PLAY", ButtonAction::Play),
+            ("DAILY", ButtonAction::Daily),
+            ("TIME ATTACK", ButtonAction::TimeAttack),
+            ("TUTORIAL", ButtonAction::Tutorial),
+            ("PLAY CODE", ButtonAction::PlayCode),
+            ("PUZZLES", ButtonAction::Puzzles),
+            ("EDITOR", ButtonAction::Editor),
+            ("SETTINGS", ButtonAction::Settings),
+            ("QUIT", ButtonAction::Quit),
+        ],
+    );
+}
+
+fn spawn_settings(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    // Setting labels are filled by `settings_labels`
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        &button_colors,
+        "SETTINGS",
+        true,
+        // Smaller buttons so every setting fits the smallest window
+        16.,
+        &[
+            ("", ButtonAction::BoardSize),
+            ("", ButtonAction::GameMode),
+            ("", ButtonAction::SafeStart),
+            ("", ButtonAction::TriggerMode),
+            ("", ButtonAction::QuestionMarks),
+            ("", ButtonAction::Theme),
+            ("", ButtonAction::Palette),
+            ("", ButtonAction::Markers),
+            ("", ButtonAction::WindowSize),
+            ("", ButtonAction::Volume),
+            ("", ButtonAction::TimeAttackReset),
+            ("BACK", ButtonAction::Menu),
+        ],
+    );
+}
+
+fn spawn_pause(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    // The pause screen is opaque so the paused board can't be studied
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        &button_colors,
+        "PAUSED",
+        true,
+        30.,
+        &[
+            ("RESUME", ButtonAction::Resume),
+            ("RESTART", ButtonAction::Restart),
+            ("COPY CODE", ButtonAction::CopyCode),
+            ("MENU", ButtonAction::Menu),
+        ],
+    );
+}
+
+fn spawn_victory(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+    flags_games: Query<&FlagsGame>,
+) {
+    let title = match flags_games.iter().find_map(|g| g.outcome) {
+        Some(FlagsOutcome::Winner(player)) => format!("PLAYER {} WINS", player + 1),
+        Some(FlagsOutcome::Draw) => "DRAW".to_string(),
+        None => "VICTORY".to_string(),
+    };
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        &button_colors,
+        &title,
+        false,
+        30.,
+        &[
+            ("PLAY AGAIN", ButtonAction::Restart),
+            ("COPY CODE", ButtonAction::CopyCode),
+            ("MENU", ButtonAction::Menu),
+        ],
+    );
+}
+
+fn spawn_defeat(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        &button_colors,
+        "DEFEAT",
+        false,
+        30.,
+        &[
+            ("TRY AGAIN", ButtonAction::Restart),
+            ("COPY CODE", ButtonAction::CopyCode),
+            ("MENU", ButtonAction::Menu),
+        ],
+    );
+}
+
+/// Ends the game on the board win and loss events.
+///
+/// Tutorial lessons end with their last step, and time attacks with their last board instead
+fn game_over_handler(
+    end_screens: Res<EndScreens>,
+    tutorial: Option<Res<Tutorial>>,
+    time_attack: Option<Res<TimeAttackRun>>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    if tutorial.is_some() || time_attack.is_some() {
+        return;
+    }
+    // An explosion on the last move is a defeat
+    if bomb_explosion_evr.iter().next().is_some() && end_screens.defeat {
+        log::info!("game lost");
+        state_wr.send(StateEvent(AppState::Defeat));
+    } else if board_completed_evr.iter().next().is_some() && end_screens.victory {
+        log::info!("game won");
+        state_wr.send(StateEvent(AppState::Victory));
+    }
+}
+
+/// Applies the clicked setting buttons
+fn settings_handler(
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut settings: ResMut<Settings>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match action {
+            ButtonAction::BoardSize => {
+                settings.difficulty = match settings.difficulty {
+                    GamePreset::Beginner => GamePreset::Intermediate,
+                    GamePreset::Intermediate => GamePreset::Expert,
+                    GamePreset::Expert => GamePreset::Beginner,
+                };
+            }
+            ButtonAction::GameMode => {
+                settings.game_mode = match settings.game_mode {
+                    GameMode::Classic => GameMode::Flags { players: 2 },
+                    GameMode::Flags { .. } => GameMode::Classic,
+                };
+            }
+            ButtonAction::SafeStart => settings.safe_start = !settings.safe_start,
+            ButtonAction::TriggerMode => {
+                settings.trigger_mode = match settings.trigger_mode {
+                    TriggerMode::Press => TriggerMode::Release,
+                    TriggerMode::Release => TriggerMode::Press,
+                };
+            }
+            ButtonAction::QuestionMarks => settings.question_marks = !settings.question_marks,
+            ButtonAction::Theme => settings.theme = settings.theme.next(),
+            ButtonAction::Palette => settings.palette = settings.palette.next(),
+            ButtonAction::Markers => settings.counter_markers = !settings.counter_markers,
+            ButtonAction::TimeAttackReset => {
+                settings.time_attack_reset = !settings.time_attack_reset
+            }
+            ButtonAction::WindowSize => {
+                let index = WINDOW_SIZES
+                    .iter()
+                    .position(|s| *s == settings.window_size)
+                    .map_or(0, |i| (i + 1) % WINDOW_SIZES.len());
+                settings.window_size = WINDOW_SIZES[index];
+            }
+            ButtonAction::Volume => {
+                // Quarter steps, wrapping from full volume to mute
+                let volume = (settings.volume * 4.).round() + 1.;
+                settings.volume = if volume > 4. { 0. } else { volume / 4. };
+            }
+            _ => (),
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "ON"
+    } else {
+        "OFF"
+    }
+}
+
+/// Shows the current value of every setting on its button
+fn settings_labels(
+    settings: Res<Settings>,
+    buttons: Query<(&ButtonAction, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (action, children) in buttons.iter() {
+        let label = match action {
+            ButtonAction::BoardSize => format!("SIZE: {}", settings.difficulty).to_uppercase(),
+            ButtonAction::GameMode => match settings.game_mode {
+                GameMode::Classic => "MODE: CLASSIC".to_string(),
+                GameMode::Flags { .. } => "MODE: FLAGS".to_string(),
+            },
+            ButtonAction::SafeStart => format!("SAFE START: {}", on_off(settings.safe_start)),
+            ButtonAction::TriggerMode => match settings.trigger_mode {
+                TriggerMode::Press => "UNCOVER ON: PRESS".to_string(),
+                TriggerMode::Release => "UNCOVER ON: RELEASE".to_string(),
+            },
+            ButtonAction::QuestionMarks => {
+                format!("QUESTION MARKS: {}", on_off(settings.question_marks))
+            }
+            ButtonAction::Theme => format!("THEME: {:?}", settings.theme).to_uppercase(),
+            ButtonAction::Palette => format!("COLORS: {:?}", settings.palette).to_uppercase(),
+            ButtonAction::Markers => format!("MARKERS: {}", on_off(settings.counter_markers)),
+            ButtonAction::WindowSize => format!(
+                "WINDOW: {}X{}",
+                settings.window_size.0, settings.window_size.1
+            ),
+            ButtonAction::Volume => format!("VOLUME: {:.0}%", settings.volume * 100.),
+            ButtonAction::TimeAttackReset => {
+                format!("RESET ON DEATH: {}", on_off(settings.time_attack_reset))
+            }
+            _ => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+            }
+        }
+    }
+}
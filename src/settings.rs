@@ -0,0 +1,302 @@
+use bevy::ecs::system::SystemParam;
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::minesweeper_core::GamePreset;
+use board_plugin::{Board, BoardAssets, BoardOptions, ColorPalette, GameMode, TriggerMode};
+#[cfg(not(target_arch = "wasm32"))]
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+
+use crate::board_code::CodeRun;
+use crate::daily::DailyRun;
+use crate::editor::EditorTest;
+use crate::puzzles::PuzzleRun;
+use crate::time_attack::TimeAttackRun;
+use crate::tutorial::Tutorial;
+
+/// Settings file name, in the user config directory
+const SETTINGS_FILE: &str = "settings.ron";
+
+/// Window sizes offered by the settings screen
+pub const WINDOW_SIZES: [(f32, f32); 3] = [(700., 750.), (900., 950.), (1200., 1000.)];
+
+/// Board color themes
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    /// Board background, tile, cover and pressed cover colors
+    pub fn colors(self) -> [Color; 4] {
+        match self {
+            Self::Light => [
+                Color::WHITE,
+                Color::DARK_GRAY,
+                Color::GRAY,
+                Color::rgb(0.4, 0.4, 0.4),
+            ],
+            Self::Dark => [
+                Color::rgb(0.1, 0.1, 0.12),
+                Color::rgb(0.2, 0.2, 0.24),
+                Color::rgb(0.42, 0.42, 0.48),
+                Color::rgb(0.3, 0.3, 0.35),
+            ],
+        }
+    }
+
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::Light,
+        }
+    }
+}
+
+/// Player settings, saved to the config file. Must be used as a resource.
+///
+/// Missing fields of the file take their default value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Board size preset of new games
+    pub difficulty: GamePreset,
+    /// Rules of new games
+    pub game_mode: GameMode,
+    pub theme: Theme,
+    /// Bomb counter colors
+    pub palette: ColorPalette,
+    /// Pip patterns next to the bomb counters
+    pub counter_markers: bool,
+    /// Marking a flagged tile again leaves a question mark
+    pub question_marks: bool,
+    /// New games start on a safe empty tile
+    pub safe_start: bool,
+    /// Uncover tiles on mouse press or release
+    pub trigger_mode: TriggerMode,
+    /// Window width and height, in logical pixels
+    pub window_size: (f32, f32),
+    /// Sound volume, from 0 to 1
+    pub volume: f32,
+    /// A death in time attack restarts the run from the first board, instead of replaying the board
+    pub time_attack_reset: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            difficulty: Default::default(),
+            game_mode: Default::default(),
+            theme: Default::default(),
+            palette: Default::default(),
+            counter_markers: false,
+            question_marks: false,
+            safe_start: true,
+            trigger_mode: Default::default(),
+            window_size: WINDOW_SIZES[0],
+            volume: 1.,
+            time_attack_reset: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Config file path, `MINESWEEPER_CONFIG` or `settings.ron` in the user config directory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn path() -> Option<PathBuf> {
+        match std::env::var_os("MINESWEEPER_CONFIG") {
+            Some(path) => Some(path.into()),
+            None => dirs::config_dir().map(|dir| dir.join("bevy_minesweeper").join(SETTINGS_FILE)),
+        }
+    }
+
+    /// Loads the config file, falling back to the default settings
+    pub fn load() -> Self {
+        load_data(SETTINGS_FILE)
+    }
+
+    /// Writes the settings to the config file
+    pub fn save(&self) {
+        save_data(SETTINGS_FILE, self);
+    }
+
+    /// Writes the game rules of the settings to the board options
+    pub fn apply_to_options(&self, board_options: &mut BoardOptions) {
+        let options = self.difficulty.options();
+        board_options.map_size = options.map_size;
+        board_options.bomb_count = options.bomb_count;
+        board_options.safe_start = self.safe_start;
+        board_options.trigger_mode = self.trigger_mode;
+        board_options.question_marks = self.question_marks;
+        board_options.game_mode = self.game_mode;
+    }
+
+    /// Writes the theme, palette and markers of the settings to the board assets
+    pub fn apply_to_assets(&self, board_assets: &mut BoardAssets) {
+        let [board, tile, covered, pressed] = self.theme.colors();
+        board_assets.board_material.color = board;
+        board_assets.tile_material.color = tile;
+        board_assets.covered_tile_material.color = covered;
+        board_assets.pressed_tile_material.color = pressed;
+        board_assets.bomb_counter_colors = self.palette.colors();
+        board_assets.counter_markers = self.counter_markers;
+    }
+}
+
+/// Data file path, `file_name` next to the settings file
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn data_path(file_name: &str) -> Option<PathBuf> {
+    let path = Settings::path()?;
+    // The settings file itself may be renamed by `MINESWEEPER_CONFIG`
+    if file_name == SETTINGS_FILE {
+        Some(path)
+    } else {
+        Some(path.with_file_name(file_name))
+    }
+}
+
+/// Loads a data file next to the settings file, falling back to the default data
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn load_data<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    let path = match data_path(file_name) {
+        Some(p) => p,
+        None => return T::default(),
+    };
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        // Nothing saved yet
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return T::default(),
+        Err(e) => {
+            log::error!("Failed to read {}: {}", path.display(), e);
+            return T::default();
+        }
+    };
+    match ron::from_str(&content) {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("Invalid data in {}: {}", path.display(), e);
+            T::default()
+        }
+    }
+}
+
+/// Browsers have no data files, every session starts with the default data
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn load_data<T: Default>(_file_name: &str) -> T {
+    T::default()
+}
+
+/// Writes a data file next to the settings file
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn save_data<T: Serialize>(file_name: &str, data: &T) {
+    let path = match data_path(file_name) {
+        Some(p) => p,
+        None => return,
+    };
+    let content = match ron::ser::to_string_pretty(data, Default::default()) {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Failed to serialize {}: {}", file_name, e);
+            return;
+        }
+    };
+    if let Some(dir) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            log::error!("Failed to create {}: {}", dir.display(), e);
+            return;
+        }
+    }
+    match std::fs::write(&path, content) {
+        Ok(()) => log::debug!("Saved {}", path.display()),
+        Err(e) => log::error!("Failed to write {}: {}", path.display(), e),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn save_data<T: Serialize>(_file_name: &str, _data: &T) {}
+
+/// Modes playing their own boards, each restores the board options it replaced when it ends
+#[derive(SystemParam)]
+pub struct ModeRuns<'w, 's> {
+    daily: Option<ResMut<'w, DailyRun>>,
+    code: Option<ResMut<'w, CodeRun>>,
+    puzzle: Option<ResMut<'w, PuzzleRun>>,
+    time_attack: Option<ResMut<'w, TimeAttackRun>>,
+    editor: Option<ResMut<'w, EditorTest>>,
+    tutorial: Option<ResMut<'w, Tutorial>>,
+    #[system_param(ignore)]
+    marker: std::marker::PhantomData<&'s ()>,
+}
+
+impl ModeRuns<'_, '_> {
+    /// Is a mode playing its own board
+    pub fn is_running(&self) -> bool {
+        self.daily.is_some()
+            || self.code.is_some()
+            || self.puzzle.is_some()
+            || self.time_attack.is_some()
+            || self.editor.is_some()
+            || self.tutorial.is_some()
+    }
+
+    /// Board options restored by the running mode, if any
+    fn previous_options(&mut self) -> Option<&mut BoardOptions> {
+        self.daily
+            .as_deref_mut()
+            .map(|r| &mut r.previous_options)
+            .or(self.code.as_deref_mut().map(|r| &mut r.previous_options))
+            .or(self.puzzle.as_deref_mut().map(|r| &mut r.previous_options))
+            .or(self
+                .time_attack
+                .as_deref_mut()
+                .map(|r| &mut r.previous_options))
+            .or(self.editor.as_deref_mut().map(|r| &mut r.previous_options))
+            .or(self
+                .tutorial
+                .as_deref_mut()
+                .map(|r| &mut r.previous_options))
+    }
+}
+
+/// Applies and saves the changed settings.
+///
+/// Board size, mode and safe start apply to the next game, the rest applies to the current one.
+/// Modes playing their own boards keep them, the settings apply once they end
+pub fn apply_settings(
+    settings: Res<Settings>,
+    mut board_options: ResMut<BoardOptions>,
+    mut runs: ModeRuns,
+    mut board_assets: ResMut<BoardAssets>,
+    mut boards: Query<&mut Board>,
+    mut windows: ResMut<Windows>,
+) {
+    // The startup settings are applied by `setup_board`
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+    match runs.previous_options() {
+        Some(previous_options) => {
+            settings.apply_to_options(previous_options);
+            board_options.trigger_mode = settings.trigger_mode;
+            board_options.question_marks = settings.question_marks;
+        }
+        None => settings.apply_to_options(&mut board_options),
+    }
+    settings.apply_to_assets(&mut board_assets);
+    for mut board in boards.iter_mut() {
+        board.options.trigger_mode = settings.trigger_mode;
+        board.options.question_marks = settings.question_marks;
+    }
+    if let Some(window) = windows.get_primary_mut() {
+        let (width, height) = settings.window_size;
+        if window.requested_width() != width || window.requested_height() != height {
+            window.set_resolution(width, height);
+        }
+    }
+    settings.save();
+}
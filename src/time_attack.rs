@@ -0,0 +1,308 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::events::{BoardCompletedEvent, BombExplosionEvent};
+use board_plugin::minesweeper_core::{GameOptions, SpeedrunCourse, SpeedrunRecords};
+use board_plugin::{BoardOptions, GameMode};
+
+use crate::buttons::ButtonAction;
+use crate::screens::spawn_summary;
+use crate::settings::{load_data, save_data, Settings};
+use crate::{AppState, StateEvent};
+
+/// Time attack best splits file, next to the settings file
+const RECORDS_FILE: &str = "time_attack.ron";
+
+/// Time attack being played. Must be used as a resource, the run goes on while it exists
+#[derive(Debug)]
+pub struct TimeAttackRun {
+    pub course: SpeedrunCourse,
+    /// Cumulative play time, in seconds
+    pub time: f32,
+    /// Cumulative time at the end of every cleared board, the current board is the next one
+    pub splits: Vec<f32>,
+    /// Uncovered bombs, the board is replayed when runs don't reset on death
+    pub deaths: u32,
+    /// Did the run end
+    pub finished: bool,
+    /// Is the run the new best run of the course
+    pub best: bool,
+    /// Board options to restore when leaving the run
+    pub(crate) previous_options: BoardOptions,
+}
+
+impl TimeAttackRun {
+    /// Starts the run over from the first board
+    fn reset(&mut self) {
+        self.time = 0.;
+        self.splits.clear();
+        self.deaths = 0;
+        self.finished = false;
+        self.best = false;
+    }
+}
+
+/// Time attack splits panel, shown for the whole run
+#[derive(Debug, Copy, Clone, Component)]
+struct TimeAttackHud;
+
+/// Split times of the time attack panel
+#[derive(Debug, Copy, Clone, Component)]
+struct TimeAttackText;
+
+/// Loads the best splits and registers the time attack systems
+pub fn setup_time_attack(app: &mut App) {
+    app.insert_resource(load_data::<SpeedrunRecords>(RECORDS_FILE))
+        .add_system(time_attack_buttons)
+        .add_system(time_attack_hud)
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_time_attack))
+        .add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(time_attack_timer)
+                .with_system(time_attack_outcome),
+        )
+        .add_system_set(SystemSet::on_enter(AppState::Victory).with_system(time_attack_summary))
+        .add_system_set(SystemSet::on_enter(AppState::Defeat).with_system(time_attack_summary));
+}
+
+/// Sets up the board options for a board of the course
+fn apply_board(board_options: &mut BoardOptions, options: &GameOptions) {
+    board_options.map_size = options.map_size;
+    board_options.bomb_count = options.bomb_count;
+    board_options.safe_start = options.safe_start;
+    board_options.seed = options.seed;
+    board_options.game_mode = GameMode::Classic;
+    board_options.tile_map = None;
+    board_options.start = None;
+}
+
+/// Starts a run from the main menu, and restarts it from the pause and end screens
+fn time_attack_buttons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    run: Option<ResMut<TimeAttackRun>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    let clicked = |target| {
+        interactions
+            .iter()
+            .any(|(interaction, action)| *interaction == Interaction::Clicked && *action == target)
+    };
+    match run {
+        // The restart itself is requested by the input handler
+        Some(mut run) if clicked(ButtonAction::Restart) => {
+            log::info!("restarting time attack {}", run.course.name);
+            run.reset();
+            apply_board(&mut board_options, &run.course.boards[0]);
+        }
+        None if clicked(ButtonAction::TimeAttack) => {
+            let course = SpeedrunCourse::beginner();
+            log::info!("starting time attack {}", course.name);
+            let previous_options = board_options.clone();
+            apply_board(&mut board_options, &course.boards[0]);
+            commands.insert_resource(TimeAttackRun {
+                course,
+                time: 0.,
+                splits: Vec::new(),
+                deaths: 0,
+                finished: false,
+                best: false,
+                previous_options,
+            });
+            spawn_hud(&mut commands, &asset_server);
+            state_wr.send(StateEvent(AppState::InGame));
+        }
+        _ => (),
+    }
+}
+
+fn spawn_hud(commands: &mut Commands, asset_server: &AssetServer) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    // Below the top bar
+                    top: Val::Px(60.),
+                    right: Val::Px(10.),
+                    ..Default::default()
+                },
+                padding: Rect::all(Val::Px(5.)),
+                ..Default::default()
+            },
+            color: Color::rgba(0.15, 0.15, 0.15, 0.85).into(),
+            ..Default::default()
+        })
+        .insert(TimeAttackHud)
+        .insert(Name::new("Time Attack HUD"))
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        "",
+                        TextStyle {
+                            font: asset_server.load("fonts/pixeled.ttf"),
+                            font_size: 12.,
+                            color: Color::WHITE,
+                        },
+                        Default::default(),
+                    ),
+                    ..Default::default()
+                })
+                .insert(TimeAttackText);
+        });
+}
+
+/// Leaves the run, restores the board options and removes the splits panel
+fn end_time_attack(
+    mut commands: Commands,
+    run: Option<Res<TimeAttackRun>>,
+    mut board_options: ResMut<BoardOptions>,
+    huds: Query<Entity, With<TimeAttackHud>>,
+) {
+    if let Some(run) = run {
+        *board_options = run.previous_options.clone();
+        commands.remove_resource::<TimeAttackRun>();
+    }
+    for hud in huds.iter() {
+        commands.entity(hud).despawn_recursive();
+    }
+}
+
+fn time_attack_timer(time: Res<Time>, run: Option<ResMut<TimeAttackRun>>) {
+    if let Some(mut run) = run {
+        if !run.finished {
+            run.time += time.delta_seconds();
+        }
+    }
+}
+
+/// Moves on to the next board, and ends the run on its last board or on death
+#[allow(clippy::too_many_arguments)]
+fn time_attack_outcome(
+    run: Option<ResMut<TimeAttackRun>>,
+    settings: Res<Settings>,
+    mut records: ResMut<SpeedrunRecords>,
+    mut board_options: ResMut<BoardOptions>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    let mut run = match run {
+        Some(r) if !r.finished => r,
+        _ => return,
+    };
+    // An explosion on the last move is a death
+    if bomb_explosion_evr.iter().count() > 0 {
+        run.deaths += 1;
+        if settings.time_attack_reset {
+            log::info!("time attack lost on board {}", run.splits.len() + 1);
+            run.finished = true;
+            state_wr.send(StateEvent(AppState::Defeat));
+        } else {
+            // The board options still hold the current board
+            log::info!("replaying board {}", run.splits.len() + 1);
+            state_wr.send(StateEvent(AppState::InGame));
+        }
+    } else if board_completed_evr.iter().count() > 0 {
+        let time = run.time;
+        run.splits.push(time);
+        match run.course.boards.get(run.splits.len()).cloned() {
+            Some(next) => {
+                apply_board(&mut board_options, &next);
+                state_wr.send(StateEvent(AppState::InGame));
+            }
+            None => {
+                log::info!("time attack {} cleared in {:.1}s", run.course.name, time);
+                run.finished = true;
+                run.best = records.finish(&run.course, &run.splits);
+                save_data(RECORDS_FILE, &*records);
+                state_wr.send(StateEvent(AppState::Victory));
+            }
+        }
+    }
+}
+
+/// Difference to the best run, if any
+fn delta_text(delta: Option<f32>) -> String {
+    delta.map_or(String::new(), |d| format!(" {:+.1}", d))
+}
+
+/// Shows the splits of the run against the best run, live for the current board
+fn time_attack_hud(
+    run: Option<Res<TimeAttackRun>>,
+    records: Res<SpeedrunRecords>,
+    mut texts: Query<&mut Text, With<TimeAttackText>>,
+) {
+    let run = match run {
+        Some(r) if r.is_changed() => r,
+        _ => return,
+    };
+    let name = &run.course.name;
+    let boards = run.course.boards.len();
+    let current = run.splits.len();
+    let mut lines = vec![format!(
+        "BOARD {}/{} - {:.1}S",
+        (current + 1).min(boards),
+        boards,
+        run.time
+    )];
+    for (i, split) in run.splits.iter().enumerate() {
+        let delta = records.delta(name, i, *split);
+        lines.push(format!("{}. {:.1}S{}", i + 1, split, delta_text(delta)));
+    }
+    if !run.finished && current < boards {
+        let delta = records.delta(name, current, run.time);
+        lines.push(format!(
+            "{}. {:.1}S{}",
+            current + 1,
+            run.time,
+            delta_text(delta)
+        ));
+    }
+    if run.deaths > 0 {
+        lines.push(format!("DEATHS: {}", run.deaths));
+    }
+    for mut text in texts.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+/// Shows the run result on the end screens
+fn time_attack_summary(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    run: Option<Res<TimeAttackRun>>,
+    records: Res<SpeedrunRecords>,
+) {
+    let run = match run {
+        Some(r) => r,
+        None => return,
+    };
+    let name = &run.course.name;
+    let boards = run.course.boards.len();
+    let mut text = if run.splits.len() < boards {
+        format!(
+            "Time attack {}: lost on board {}/{} at {:.1}s",
+            name,
+            run.splits.len() + 1,
+            boards,
+            run.time
+        )
+    } else if run.best {
+        format!("Time attack {}: {:.1}s, new best", name, run.time)
+    } else {
+        let delta = records.delta(name, boards - 1, run.time);
+        format!(
+            "Time attack {}: {:.1}s{} on best",
+            name,
+            run.time,
+            delta_text(delta)
+        )
+    };
+    if run.deaths > 0 {
+        text += &format!(" - deaths: {}", run.deaths);
+    }
+    spawn_summary(&mut commands, &asset_server, "Time Attack Summary", text);
+}
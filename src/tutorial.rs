@@ -0,0 +1,416 @@
+use bevy::log;
+use bevy::prelude::*;
+use board_plugin::components::{AllowedMoves, Coordinates};
+use board_plugin::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
+use board_plugin::minesweeper_core::TileMap;
+use board_plugin::{Board, BoardAssets, BoardOptions, BoardSystem, GameMode, TilePress};
+
+use crate::buttons::{setup_single_menu, ButtonAction, ButtonColors};
+use crate::{AppState, StateEvent};
+
+/// Window space reserved above the board for the tutorial panel
+const PANEL_HEIGHT: f32 = 140.;
+/// Cover color of the tiles the current step expects
+const HIGHLIGHT_COLOR: Color = Color::rgb(0.95, 0.75, 0.2);
+
+/// Player move expected by a tutorial step, on tile coordinates from the bottom left
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TutorialMove {
+    Uncover(u16, u16),
+    Flag(u16, u16),
+    Chord(u16, u16),
+}
+
+/// Tutorial step, moving on once its move is played
+struct Step {
+    message: &'static str,
+    expected: TutorialMove,
+}
+
+/// Tutorial lesson, played on a hand-made board
+struct Lesson {
+    title: &'static str,
+    /// Board rows from the top, with `*` for bombs and `.` for safe tiles
+    rows: &'static [&'static str],
+    /// Does the board start with its first empty tile uncovered
+    safe_start: bool,
+    steps: &'static [Step],
+    /// Message shown once every step is done
+    outro: &'static str,
+}
+
+const LESSONS: [Lesson; 4] = [
+    Lesson {
+        title: "UNCOVERING",
+        rows: &["....*", ".....", ".....", "*...."],
+        safe_start: false,
+        steps: &[Step {
+            message: "Left click the highlighted tile to uncover it.",
+            expected: TutorialMove::Uncover(2, 1),
+        }],
+        outro: "Empty tiles uncover their neighbours. Numbers tell how many mines touch them, \
+                the board is cleared once every safe tile is uncovered.",
+    },
+    Lesson {
+        title: "FLAGS AND CHORDS",
+        rows: &[".....", ".....", ".....", ".*..."],
+        safe_start: false,
+        steps: &[
+            Step {
+                message: "Left click the highlighted tile to uncover it.",
+                expected: TutorialMove::Uncover(4, 3),
+            },
+            Step {
+                message: "The 1 right of the highlighted tile touches no other covered tile, \
+                          so it hides a mine. Right click it to flag it.",
+                expected: TutorialMove::Flag(1, 0),
+            },
+            Step {
+                message: "The 1 above the flag has found its mine. Middle click it, or press \
+                          both buttons on it, to uncover the highlighted tile.",
+                expected: TutorialMove::Chord(1, 1),
+            },
+        ],
+        outro: "Chording uncovers the unflagged neighbours of a number once all its mines are \
+                flagged. A wrong flag uncovers a mine!",
+    },
+    Lesson {
+        title: "THE 1-1 PATTERN",
+        rows: &[".*..*", ".....", ".....", "....."],
+        safe_start: true,
+        steps: &[Step {
+            message: "The corner 1 and its neighbour share the same mine, in the two tiles \
+                      above the corner. The third tile is safe, uncover it.",
+            expected: TutorialMove::Uncover(2, 3),
+        }],
+        outro: "1-1: when a 1 touches a wall, the tile beyond the 1 next to it is safe.",
+    },
+    Lesson {
+        title: "THE 1-2 PATTERN",
+        rows: &[".*.**", ".....", ".....", "....."],
+        safe_start: true,
+        steps: &[
+            Step {
+                message: "The 2 needs two mines in the three tiles above it, the 1 on its left \
+                          allows only one in the first two. Flag the third tile.",
+                expected: TutorialMove::Flag(3, 3),
+            },
+            Step {
+                message: "The two 1s on the left form a 1-1 pattern, uncover their safe tile.",
+                expected: TutorialMove::Uncover(2, 3),
+            },
+        ],
+        outro: "1-2: the tile beyond the 2 is a mine. You are ready to play!",
+    },
+];
+
+impl Lesson {
+    fn tile_map(&self) -> TileMap {
+        TileMap::from_rows(self.rows).expect("Invalid lesson board")
+    }
+}
+
+/// Tutorial progression. Must be used as a resource, the tutorial runs while it exists
+#[derive(Debug)]
+pub struct Tutorial {
+    /// Current lesson
+    pub lesson: usize,
+    /// Current step of the lesson, equal to its step count once the lesson is done
+    pub step: usize,
+    /// Board options to restore when the tutorial ends
+    pub(crate) previous_options: BoardOptions,
+}
+
+impl Tutorial {
+    fn current_lesson(&self) -> &'static Lesson {
+        &LESSONS[self.lesson]
+    }
+
+    fn current_step(&self) -> Option<&'static Step> {
+        self.current_lesson().steps.get(self.step)
+    }
+
+    /// Writes the board of the current lesson to the board options
+    fn apply_to_options(&self, board_options: &mut BoardOptions) {
+        let lesson = self.current_lesson();
+        board_options.tile_map = Some(lesson.tile_map());
+        board_options.safe_start = lesson.safe_start;
+        board_options.game_mode = GameMode::Classic;
+        board_options.question_marks = false;
+        board_options.margins.top = PANEL_HEIGHT;
+    }
+
+    /// Moves the current step expects, the other moves are ignored by the board
+    fn allowed_moves(&self) -> AllowedMoves {
+        let mut allowed = AllowedMoves::default();
+        let (tiles, x, y) = match self.current_step().map(|s| s.expected) {
+            Some(TutorialMove::Uncover(x, y)) => (&mut allowed.uncover, x, y),
+            Some(TutorialMove::Flag(x, y)) => (&mut allowed.mark, x, y),
+            Some(TutorialMove::Chord(x, y)) => (&mut allowed.chord, x, y),
+            None => return allowed,
+        };
+        tiles.insert(Coordinates { x, y });
+        allowed
+    }
+}
+
+/// Tutorial message panel
+#[derive(Debug, Copy, Clone, Component)]
+pub struct TutorialPanel;
+
+/// Registers the tutorial systems
+pub fn setup_tutorial(app: &mut App) {
+    app.add_system(start_tutorial)
+        .add_system_set(SystemSet::on_enter(AppState::MainMenu).with_system(end_tutorial))
+        .add_system_set(SystemSet::on_enter(AppState::InGame).with_system(start_lesson))
+        .add_system_set(SystemSet::on_exit(AppState::InGame).with_system(despawn_panel))
+        .add_system_set(
+            SystemSet::on_update(AppState::InGame)
+                .with_system(
+                    tutorial_moves
+                        .label("tutorial_moves")
+                        .after(BoardSystem::Input),
+                )
+                .with_system(next_lesson)
+                .with_system(tutorial_step.after("tutorial_moves"))
+                .with_system(highlight_tiles.after(BoardSystem::Input)),
+        );
+}
+
+/// Starts the tutorial from its first lesson
+fn start_tutorial(
+    mut commands: Commands,
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked || *action != ButtonAction::Tutorial {
+            continue;
+        }
+        log::info!("starting tutorial");
+        let tutorial = Tutorial {
+            lesson: 0,
+            step: 0,
+            previous_options: board_options.clone(),
+        };
+        tutorial.apply_to_options(&mut board_options);
+        commands.insert_resource(tutorial);
+        state_wr.send(StateEvent(AppState::InGame));
+    }
+}
+
+/// Ends the tutorial and restores the board options
+fn end_tutorial(
+    mut commands: Commands,
+    tutorial: Option<Res<Tutorial>>,
+    mut board_options: ResMut<BoardOptions>,
+) {
+    if let Some(tutorial) = tutorial {
+        log::info!("ending tutorial");
+        *board_options = tutorial.previous_options.clone();
+        commands.remove_resource::<Tutorial>();
+    }
+}
+
+/// A regenerated lesson board starts from its first step
+fn start_lesson(tutorial: Option<ResMut<Tutorial>>) {
+    if let Some(mut tutorial) = tutorial {
+        tutorial.step = 0;
+    }
+}
+
+fn despawn_panel(mut commands: Commands, panels: Query<Entity, With<TutorialPanel>>) {
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+}
+
+/// Moves on to the next step when its move is played
+fn tutorial_moves(
+    tutorial: Option<ResMut<Tutorial>>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+) {
+    let mut tutorial = match tutorial {
+        Some(t) => t,
+        None => return,
+    };
+    let moves = tile_trigger_evr
+        .iter()
+        .map(|e| TutorialMove::Uncover(e.coords.x, e.coords.y))
+        .chain(
+            tile_mark_evr
+                .iter()
+                .map(|e| TutorialMove::Flag(e.coords.x, e.coords.y)),
+        )
+        .chain(
+            tile_chord_evr
+                .iter()
+                .map(|e| TutorialMove::Chord(e.coords.x, e.coords.y)),
+        );
+    for played in moves {
+        if tutorial.current_step().map(|s| s.expected) == Some(played) {
+            log::info!("tutorial step {} done", tutorial.step + 1);
+            tutorial.step += 1;
+        }
+    }
+}
+
+/// Loads the next lesson, or leaves the tutorial after the last one
+fn next_lesson(
+    interactions: Query<(&Interaction, &ButtonAction), Changed<Interaction>>,
+    tutorial: Option<ResMut<Tutorial>>,
+    mut board_options: ResMut<BoardOptions>,
+    mut state_wr: EventWriter<StateEvent>,
+) {
+    let mut tutorial = match tutorial {
+        Some(t) => t,
+        None => return,
+    };
+    for (interaction, action) in interactions.iter() {
+        if *interaction != Interaction::Clicked || *action != ButtonAction::NextLesson {
+            continue;
+        }
+        if tutorial.lesson + 1 < LESSONS.len() {
+            tutorial.lesson += 1;
+            tutorial.step = 0;
+            tutorial.apply_to_options(&mut board_options);
+            state_wr.send(StateEvent(AppState::InGame));
+        } else {
+            state_wr.send(StateEvent(AppState::MainMenu));
+        }
+    }
+}
+
+/// Shows the message of the current step and restricts the board to its move
+#[allow(clippy::too_many_arguments)]
+fn tutorial_step(
+    mut commands: Commands,
+    tutorial: Option<Res<Tutorial>>,
+    asset_server: Res<AssetServer>,
+    button_colors: Res<ButtonColors>,
+    boards: Query<Entity, With<Board>>,
+    new_boards: Query<(), Added<Board>>,
+    panels: Query<Entity, With<TutorialPanel>>,
+) {
+    let tutorial = match tutorial {
+        Some(t) => t,
+        None => return,
+    };
+    if !tutorial.is_changed() && new_boards.is_empty() {
+        return;
+    }
+    for board in boards.iter() {
+        commands.entity(board).insert(tutorial.allowed_moves());
+    }
+    for panel in panels.iter() {
+        commands.entity(panel).despawn_recursive();
+    }
+    let lesson = tutorial.current_lesson();
+    let message = tutorial.current_step().map_or(lesson.outro, |s| s.message);
+    let font = asset_server.load("fonts/pixeled.ttf");
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(0.),
+                    left: Val::Px(0.),
+                    ..Default::default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Px(PANEL_HEIGHT)),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..Default::default()
+            },
+            color: Color::rgba(0.15, 0.15, 0.15, 0.85).into(),
+            ..Default::default()
+        })
+        // Tracking interaction keeps the clicks on the panel from reaching the board
+        .insert(Interaction::default())
+        .insert(TutorialPanel)
+        .insert(Name::new("Tutorial Panel"))
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect::all(Val::Px(5.)),
+                    max_size: Size::new(Val::Px(640.), Val::Undefined),
+                    ..Default::default()
+                },
+                text: Text::with_section(
+                    format!(
+                        "LESSON {}/{} - {}\n{}",
+                        tutorial.lesson + 1,
+                        LESSONS.len(),
+                        lesson.title,
+                        message
+                    ),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 12.,
+                        color: Color::WHITE,
+                    },
+                    Default::default(),
+                ),
+                ..Default::default()
+            });
+            if tutorial.current_step().is_none() {
+                let text = if tutorial.lesson + 1 < LESSONS.len() {
+                    "NEXT"
+                } else {
+                    "FINISH"
+                };
+                setup_single_menu(
+                    parent,
+                    text,
+                    button_colors.normal.into(),
+                    font,
+                    16.,
+                    ButtonAction::NextLesson,
+                );
+            }
+        });
+}
+
+/// Highlights the covers of the tiles the current step expects a move on
+fn highlight_tiles(
+    tutorial: Option<Res<Tutorial>>,
+    board_assets: Res<BoardAssets>,
+    press: Res<TilePress>,
+    boards: Query<(&Board, &AllowedMoves)>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    if tutorial.is_none() {
+        return;
+    }
+    for (board, allowed) in boards.iter() {
+        // Chords are highlighted on the tiles they uncover
+        let chorded: Vec<Entity> = allowed
+            .chord
+            .iter()
+            .flat_map(|c| board.adjacent_unmarked_tiles(*c))
+            .collect();
+        for (coords, cover) in board.covered_tiles.iter() {
+            // Pressed covers are colored by the press preview
+            if press.pressed.contains(cover) {
+                continue;
+            }
+            let highlighted = allowed.uncover.contains(coords)
+                || allowed.mark.contains(coords)
+                || chorded.contains(cover);
+            let color = if highlighted {
+                HIGHLIGHT_COLOR
+            } else {
+                board_assets.covered_tile_material.color
+            };
+            if let Ok(mut sprite) = sprites.get_mut(*cover) {
+                if sprite.color != color {
+                    sprite.color = color;
+                }
+            }
+        }
+    }
+}